@@ -11,6 +11,7 @@ use flume::{unbounded, TryRecvError};
 use log::{error, info, warn};
 use static_toml::static_toml;
 use std::{
+    collections::{btree_map::Entry, BTreeMap},
     fs,
     path::{Path, PathBuf},
     time::Duration,
@@ -19,13 +20,24 @@ use strum::{AsRefStr, EnumIter, IntoEnumIterator};
 
 use crate::{
     archive_file::ArchiveApp,
+    page_bench,
+    page_bench::PageBench,
     page_get::PageGet,
+    page_liveliness,
+    page_liveliness::PageLiveliness,
     page_put::PagePut,
+    page_queryable,
+    page_queryable::PageQueryable,
+    page_scout,
+    page_scout::PageScout,
     page_session,
     page_session::PageSession,
     page_sub,
     page_sub::PageSub,
-    task_zenoh::{start_async, MsgGuiToZenoh, MsgZenohToGui, Receiver, Sender},
+    task_zenoh::{
+        start_async, start_embedded_node, start_scout, MsgGuiToZenoh, MsgZenohToGui, Receiver,
+        Sender,
+    },
 };
 
 static_toml! {
@@ -39,39 +51,82 @@ static EGUI_VERSION: &'static str = CARGO_INFO.dependencies.eframe.version;
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, AsRefStr, EnumIter)]
 pub enum Page {
     Session,
+    Scout,
     Sub,
     Get,
     Put,
+    Queryable,
+    Liveliness,
+    Bench,
+}
+
+// 每个打开的配置文件对应一个 loop_zenoh
+struct ZenohSession {
+    sender: Sender<MsgGuiToZenoh>,
+    receiver: Receiver<MsgZenohToGui>,
+    terminated: Option<String>, // loop_zenoh 退出时发送的原因
 }
 
 pub struct HammerApp {
     app_config_path: Option<PathBuf>,
-    sender_to_zenoh: Option<Sender<MsgGuiToZenoh>>,
-    receiver_from_zenoh: Option<Receiver<MsgZenohToGui>>,
+    sessions: BTreeMap<u64, ZenohSession>, // <config file id, session>
+    sub_sessions: BTreeMap<u64, u64>,      // <sub id, config file id>
+    put_sessions: BTreeMap<u64, u64>,      // <put id, config file id>
+    publisher_sessions: BTreeMap<u64, u64>, // <put id, config file id>
+    get_sessions: BTreeMap<u64, u64>,      // <get id, config file id>
+    querier_sessions: BTreeMap<u64, u64>,  // <get id, config file id>
+    queryable_sessions: BTreeMap<u64, u64>, // <queryable id, config file id>
+    token_sessions: BTreeMap<u64, u64>,    // <liveliness token id, config file id>
+    liveliness_sub_session: Option<u64>,   // config file id
+    liveliness_get_session: Option<u64>,   // config file id
+    bench_session: Option<u64>,            // config file id
+    redeclare_subs: BTreeMap<u64, Vec<u64>>, // <config file id, sub ids>
+    scout: Option<(Receiver<MsgZenohToGui>, Sender<()>)>,
+    embedded_node: Option<(Receiver<MsgZenohToGui>, Sender<()>)>,
     opened_file: Option<PathBuf>,
     file_dialog: Option<FileDialog>,
     show_help_about: bool,
     selected_page: Page,
     p_session: PageSession,
+    p_scout: PageScout,
     p_sub: PageSub,
     p_get: PageGet,
     p_put: PagePut,
+    p_queryable: PageQueryable,
+    p_liveliness: PageLiveliness,
+    p_bench: PageBench,
 }
 
 impl Default for HammerApp {
     fn default() -> Self {
         HammerApp {
-            sender_to_zenoh: None,
-            receiver_from_zenoh: None,
+            sessions: BTreeMap::new(),
+            sub_sessions: BTreeMap::new(),
+            put_sessions: BTreeMap::new(),
+            publisher_sessions: BTreeMap::new(),
+            get_sessions: BTreeMap::new(),
+            querier_sessions: BTreeMap::new(),
+            queryable_sessions: BTreeMap::new(),
+            token_sessions: BTreeMap::new(),
+            liveliness_sub_session: None,
+            liveliness_get_session: None,
+            bench_session: None,
+            redeclare_subs: BTreeMap::new(),
+            scout: None,
+            embedded_node: None,
             app_config_path: None,
             opened_file: None,
             file_dialog: None,
             show_help_about: false,
             selected_page: Page::Session,
             p_session: PageSession::default(),
+            p_scout: PageScout::default(),
             p_sub: PageSub::default(),
             p_get: PageGet::default(),
             p_put: PagePut::default(),
+            p_queryable: PageQueryable::default(),
+            p_liveliness: PageLiveliness::default(),
+            p_bench: PageBench::default(),
         }
     }
 }
@@ -79,12 +134,18 @@ impl Default for HammerApp {
 impl eframe::App for HammerApp {
     fn update(&mut self, ctx: &Context, frame: &mut Frame) {
         self.processing_zenoh_msg();
-        self.processing_page_session_events();
+        self.p_session.poll_reopen();
+        self.processing_page_session_events(ctx);
+        self.processing_page_scout_events(ctx);
         self.processing_page_sub_events();
         self.processing_page_put_events();
         self.processing_page_get_events();
+        self.processing_page_queryable_events();
+        self.processing_page_liveliness_events();
+        self.processing_page_bench_events();
         self.show_ui(ctx, frame);
-        ctx.request_repaint_after(Duration::from_millis(100));
+        // zenoh 的消息到达时会请求重绘, 这里只是兜底
+        ctx.request_repaint_after(Duration::from_secs(1));
     }
 }
 
@@ -108,14 +169,26 @@ impl HammerApp {
             Page::Session => {
                 self.p_session.show(ctx);
             }
+            Page::Scout => {
+                self.p_scout.show(ctx);
+            }
             Page::Sub => {
-                self.p_sub.show(ctx);
+                self.p_sub.show(ctx, &self.p_session.session_list());
             }
             Page::Get => {
-                self.p_get.show(ctx);
+                self.p_get.show(ctx, &self.p_session.session_list());
             }
             Page::Put => {
-                self.p_put.show(ctx);
+                self.p_put.show(ctx, &self.p_session.session_list());
+            }
+            Page::Queryable => {
+                self.p_queryable.show(ctx);
+            }
+            Page::Liveliness => {
+                self.p_liveliness.show(ctx);
+            }
+            Page::Bench => {
+                self.p_bench.show(ctx);
             }
         }
 
@@ -305,6 +378,10 @@ impl HammerApp {
         self.p_sub.load(data.page_sub)?;
         self.p_put.load(data.page_put)?;
         self.p_get.load(data.page_get)?;
+        self.p_queryable.load(data.page_queryable)?;
+        self.p_liveliness.load(data.page_liveliness)?;
+        self.p_bench.load(data.page_bench)?;
+        self.p_scout.load(data.page_scout)?;
         Ok(())
     }
 
@@ -314,67 +391,251 @@ impl HammerApp {
             page_sub: (&self.p_sub).into(),
             page_put: (&self.p_put).into(),
             page_get: (&self.p_get).into(),
+            page_queryable: (&self.p_queryable).into(),
+            page_liveliness: (&self.p_liveliness).into(),
+            page_bench: (&self.p_bench).into(),
+            page_scout: (&self.p_scout).into(),
         }
     }
 
     fn processing_zenoh_msg(&mut self) {
-        let receiver: &Receiver<MsgZenohToGui> = match &self.receiver_from_zenoh {
-            None => {
-                return;
+        let mut msgs = Vec::new();
+        let mut closed = Vec::new();
+        for (id, session) in &self.sessions {
+            loop {
+                match session.receiver.try_recv() {
+                    Ok(m) => msgs.push((*id, m)),
+                    Err(TryRecvError::Empty) => {
+                        break;
+                    }
+                    Err(TryRecvError::Disconnected) => {
+                        closed.push(*id);
+                        break;
+                    }
+                }
             }
-            Some(s) => s,
-        };
+        }
 
-        loop {
-            let r = receiver.try_recv();
-            let msg = match r {
-                Ok(m) => m,
-                Err(e) => match e {
-                    TryRecvError::Empty => {
-                        return;
-                    }
-                    TryRecvError::Disconnected => {
-                        self.receiver_from_zenoh = None;
-                        self.p_session.set_connected(None);
-                        return;
+        for (session_id, msg) in msgs {
+            self.processing_session_msg(session_id, msg);
+        }
+
+        self.processing_scout_msg();
+        self.processing_embedded_node_msg();
+
+        for id in closed {
+            // 没有收到退出原因时, loop_zenoh 是异常退出的
+            let reason = self.sessions.remove(&id).and_then(|s| s.terminated);
+            // 正常退出时已经发送了 Del/Undeclare 的消息, 这里兜底清理页面状态
+            let sub_ids = take_session_items(&mut self.sub_sessions, id);
+            for sub_id in &sub_ids {
+                self.p_sub.processing_del_sub_res(*sub_id);
+            }
+            for put_id in take_session_items(&mut self.put_sessions, id) {
+                self.p_put.processing_session_terminated(put_id);
+            }
+            for put_id in take_session_items(&mut self.publisher_sessions, id) {
+                self.p_put.processing_session_terminated(put_id);
+            }
+            for get_id in take_session_items(&mut self.get_sessions, id) {
+                self.p_get
+                    .processing_get_err(get_id, "session terminated".to_string());
+            }
+            for get_id in take_session_items(&mut self.querier_sessions, id) {
+                self.p_get.processing_undeclare_querier_res(get_id);
+            }
+            for queryable_id in take_session_items(&mut self.queryable_sessions, id) {
+                self.p_queryable.processing_del_queryable_res(queryable_id);
+            }
+            for token_id in take_session_items(&mut self.token_sessions, id) {
+                self.p_liveliness.processing_undeclare_token_res(token_id);
+            }
+            if self.liveliness_sub_session == Some(id) {
+                self.liveliness_sub_session = None;
+                self.p_liveliness.processing_del_sub_res();
+            }
+            if self.liveliness_get_session == Some(id) {
+                self.liveliness_get_session = None;
+                self.p_liveliness
+                    .processing_get_finish(Err("session terminated".to_string()));
+            }
+            if self.bench_session == Some(id) {
+                self.bench_session = None;
+                self.p_bench
+                    .processing_bench_finish(Err("session terminated".to_string()));
+            }
+            self.p_session.set_terminated(id, reason);
+            // 自动重新打开后重新声明这些订阅
+            if self.p_session.reopening(id) && !sub_ids.is_empty() {
+                self.redeclare_subs.entry(id).or_default().extend(sub_ids);
+            }
+        }
+    }
+
+    fn processing_session_msg(&mut self, session_id: u64, msg: MsgZenohToGui) {
+        match msg {
+            MsgZenohToGui::OpenSession(b) => {
+                match &b {
+                    Ok(id) => {
+                        if let Some(sub_ids) = self.redeclare_subs.remove(id) {
+                            if self.p_session.reopening(*id) {
+                                self.p_sub.redeclare(&sub_ids);
+                            }
+                        }
                     }
-                },
-            };
-            match msg {
-                MsgZenohToGui::OpenSession(b) => {
-                    if b.is_err() {
-                        self.sender_to_zenoh = None;
+                    Err((id, _)) => {
+                        let _ = self.sessions.remove(id);
                     }
-                    self.p_session.set_connect_result(b);
                 }
-                MsgZenohToGui::AddSubRes(res) => {
-                    let (id, r) = *res;
-                    self.p_sub.processing_add_sub_res(id, r);
+                self.p_session.set_connect_result(b);
+            }
+            MsgZenohToGui::AddSubRes(res) => {
+                let (id, r) = *res;
+                self.p_sub.processing_add_sub_res(id, r);
+            }
+            MsgZenohToGui::DelSubRes(id) => {
+                self.p_sub.processing_del_sub_res(id);
+            }
+            MsgZenohToGui::SubCB(d) => {
+                let (id, samples, dropped) = *d;
+                self.p_sub.processing_sub_cb(id, samples, dropped);
+            }
+            MsgZenohToGui::SubMiss(d) => {
+                let (id, miss) = *d;
+                self.p_sub.processing_sub_miss(id, miss);
+            }
+            MsgZenohToGui::GetRes(r) => {
+                if self.get_sessions.get(&r.0) == Some(&session_id) {
+                    self.p_get.processing_get_res(r);
                 }
-                MsgZenohToGui::DelSubRes(id) => {
-                    self.p_sub.processing_del_sub_res(id);
+            }
+            MsgZenohToGui::GetErr(d) => {
+                let (id, e) = *d;
+                if self.take_get_session(id, session_id) {
+                    self.p_get.processing_get_err(id, e);
                 }
-                MsgZenohToGui::SubCB(d) => {
-                    let (id, sample, receipt_time) = *d;
-                    self.p_sub.processing_sub_cb(id, sample, receipt_time);
+            }
+            MsgZenohToGui::GetFinish(d) => {
+                let (id, finish) = *d;
+                if self.take_get_session(id, session_id) {
+                    self.p_get.processing_get_finish(id, finish);
                 }
-                MsgZenohToGui::GetRes(r) => {
-                    self.p_get.processing_get_res(r);
+            }
+            MsgZenohToGui::DeclareQuerierRes(res) => {
+                let (id, r) = *res;
+                if r.is_err() {
+                    let _ = self.querier_sessions.remove(&id);
                 }
-                MsgZenohToGui::PutRes(r) => {
-                    self.p_put.processing_put_res(r);
+                self.p_get.processing_declare_querier_res(id, r);
+            }
+            MsgZenohToGui::UndeclareQuerierRes(id) => {
+                self.p_get.processing_undeclare_querier_res(id);
+            }
+            MsgZenohToGui::QuerierMatchingStatus(id, matching) => {
+                self.p_get.processing_querier_matching_status(id, matching);
+            }
+            MsgZenohToGui::PutRes(r) => {
+                self.p_put.processing_put_res(*r);
+            }
+            MsgZenohToGui::DeclarePublisherRes(res) => {
+                let (id, r) = *res;
+                if r.is_err() {
+                    let _ = self.publisher_sessions.remove(&id);
                 }
+                self.p_put.processing_declare_publisher_res(id, r);
+            }
+            MsgZenohToGui::UndeclarePublisherRes(id) => {
+                self.p_put.processing_undeclare_publisher_res(id);
+            }
+            MsgZenohToGui::PublisherMatchingStatus(id, matching) => {
+                self.p_put
+                    .processing_publisher_matching_status(id, matching);
+            }
+            MsgZenohToGui::PutTaskStatus(d) => {
+                let (id, status) = *d;
+                self.p_put.processing_put_task_status(id, status);
+            }
+            MsgZenohToGui::AddQueryableRes(res) => {
+                let (id, r) = *res;
+                if r.is_err() {
+                    let _ = self.queryable_sessions.remove(&id);
+                }
+                self.p_queryable.processing_add_queryable_res(id, r);
+            }
+            MsgZenohToGui::DelQueryableRes(id) => {
+                self.p_queryable.processing_del_queryable_res(id);
+            }
+            MsgZenohToGui::QueryableCB(d) => {
+                let (id, record, receipt_time) = *d;
+                self.p_queryable
+                    .processing_queryable_cb(id, record, receipt_time);
+            }
+            MsgZenohToGui::DeclareTokenRes(res) => {
+                let (id, r) = *res;
+                if r.is_err() {
+                    let _ = self.token_sessions.remove(&id);
+                }
+                self.p_liveliness.processing_declare_token_res(id, r);
+            }
+            MsgZenohToGui::UndeclareTokenRes(id) => {
+                self.p_liveliness.processing_undeclare_token_res(id);
+            }
+            MsgZenohToGui::AddLivelinessSubRes(r) => {
+                if r.is_err() {
+                    self.liveliness_sub_session = None;
+                }
+                self.p_liveliness.processing_add_sub_res(r);
+            }
+            MsgZenohToGui::DelLivelinessSubRes => {
+                self.p_liveliness.processing_del_sub_res();
+            }
+            MsgZenohToGui::LivelinessSubCB(d) => {
+                let (sample, receipt_time) = *d;
+                self.p_liveliness.processing_sub_cb(sample, receipt_time);
+            }
+            MsgZenohToGui::LivelinessGetRes(d) => {
+                let (reply, receipt_time) = *d;
+                self.p_liveliness.processing_get_res(reply, receipt_time);
+            }
+            MsgZenohToGui::LivelinessGetFinish(r) => {
+                self.liveliness_get_session = None;
+                self.p_liveliness.processing_get_finish(r);
+            }
+            MsgZenohToGui::BenchReport(r) => {
+                self.p_bench.processing_bench_report(*r);
+            }
+            MsgZenohToGui::BenchFinish(r) => {
+                self.bench_session = None;
+                self.p_bench.processing_bench_finish(r);
+            }
+            MsgZenohToGui::ShmStatus(s) => {
+                self.p_put.processing_shm_status((*s).clone());
+                self.p_get.processing_shm_status(*s);
+            }
+            MsgZenohToGui::SessionTerminated(reason) => {
+                if let Some(session) = self.sessions.get_mut(&session_id) {
+                    session.terminated = Some(reason);
+                }
+            }
+            MsgZenohToGui::SessionInfo(info) => {
+                self.p_session.processing_session_info(session_id, *info);
+            }
+            MsgZenohToGui::ScoutHello(_) | MsgZenohToGui::ScoutFinish(_) => {
+                warn!("session {session_id} got an unexpected scout msg");
+            }
+            MsgZenohToGui::EmbeddedNodeInfo(_) | MsgZenohToGui::EmbeddedNodeFinish(_) => {
+                warn!("session {session_id} got an unexpected embedded node msg");
             }
         }
     }
 
-    fn processing_page_session_events(&mut self) {
+    fn processing_page_session_events(&mut self, ctx: &Context) {
         use page_session::Event;
         while let Some(event) = self.p_session.events.pop_front() {
             match event {
                 Event::Connect(c) => {
-                    let (id, config_file_path) = *c;
-                    if self.sender_to_zenoh.is_none() {
+                    let (id, config, overrides) = *c;
+                    if let Entry::Vacant(entry) = self.sessions.entry(id) {
                         let (sender_to_gui, receiver_from_zenoh): (
                             Sender<MsgZenohToGui>,
                             Receiver<MsgZenohToGui>,
@@ -384,37 +645,170 @@ impl HammerApp {
                             Receiver<MsgGuiToZenoh>,
                         ) = unbounded();
 
-                        start_async(sender_to_gui, receiver_from_gui, id, config_file_path);
+                        start_async(
+                            sender_to_gui,
+                            receiver_from_gui,
+                            id,
+                            config,
+                            overrides,
+                            ctx.clone(),
+                        );
+
+                        let session = ZenohSession {
+                            sender: sender_to_zenoh,
+                            receiver: receiver_from_zenoh,
+                            terminated: None,
+                        };
+                        let _ = entry.insert(session);
+                    }
+                }
+                Event::Disconnect(id) => {
+                    // 等待 session 关闭后 channel 断开, 再更新页面状态
+                    if let Some(session) = self.sessions.get(&id) {
+                        let _ = session.sender.send(MsgGuiToZenoh::Close);
+                    }
+                }
+                Event::StartEmbeddedNode(d) => {
+                    if self.embedded_node.is_some() {
+                        continue;
+                    }
+                    let (sender_to_gui, receiver_from_zenoh) = unbounded();
+                    let (stop_sender, stop_receiver) = unbounded();
+                    start_embedded_node(sender_to_gui, stop_receiver, *d, ctx.clone());
+                    self.embedded_node = Some((receiver_from_zenoh, stop_sender));
+                }
+                Event::StopEmbeddedNode => {
+                    if let Some((_, stop_sender)) = &self.embedded_node {
+                        let _ = stop_sender.send(());
+                    }
+                }
+            }
+        }
+    }
+
+    fn processing_scout_msg(&mut self) {
+        let Some((receiver, _)) = &self.scout else {
+            return;
+        };
+        let (msgs, finished) = try_recv_all(receiver);
+        if finished {
+            self.scout = None;
+        }
+        for msg in msgs {
+            self.processing_scout_res(msg);
+        }
+    }
+
+    fn processing_scout_res(&mut self, msg: MsgZenohToGui) {
+        // scout 与 session 无关, 只处理 scout 的消息
+        match msg {
+            MsgZenohToGui::ScoutHello(hello) => {
+                self.p_scout.processing_scout_hello(*hello);
+            }
+            MsgZenohToGui::ScoutFinish(r) => {
+                self.p_scout.processing_scout_finish(r);
+            }
+            _ => {
+                warn!("scout got an unexpected msg");
+            }
+        }
+    }
+
+    fn processing_embedded_node_msg(&mut self) {
+        let Some((receiver, _)) = &self.embedded_node else {
+            return;
+        };
+        let (msgs, finished) = try_recv_all(receiver);
+        if finished {
+            self.embedded_node = None;
+        }
+        for msg in msgs {
+            self.processing_embedded_node_res(msg);
+        }
+    }
+
+    fn processing_embedded_node_res(&mut self, msg: MsgZenohToGui) {
+        // 内嵌节点与 session 无关, 只处理节点自身的消息
+        match msg {
+            MsgZenohToGui::EmbeddedNodeInfo(info) => {
+                self.p_session.processing_embedded_node_info(*info);
+            }
+            MsgZenohToGui::EmbeddedNodeFinish(r) => {
+                self.p_session.processing_embedded_node_finish(r);
+            }
+            _ => {
+                warn!("embedded node got an unexpected msg");
+            }
+        }
+    }
 
-                        self.sender_to_zenoh = Some(sender_to_zenoh);
-                        self.receiver_from_zenoh = Some(receiver_from_zenoh);
+    fn processing_page_scout_events(&mut self, ctx: &Context) {
+        while let Some(event) = self.p_scout.events.pop_front() {
+            match event {
+                page_scout::Event::Start(d) => {
+                    if self.scout.is_some() {
+                        continue;
+                    }
+                    let (sender_to_gui, receiver_from_zenoh) = unbounded();
+                    let (stop_sender, stop_receiver) = unbounded();
+                    start_scout(sender_to_gui, stop_receiver, *d, ctx.clone());
+                    self.scout = Some((receiver_from_zenoh, stop_sender));
+                }
+                page_scout::Event::Stop => {
+                    if let Some((_, stop_sender)) = &self.scout {
+                        let _ = stop_sender.send(());
                     }
                 }
-                Event::Disconnect => {
-                    if let Some(sender) = &self.sender_to_zenoh {
-                        let _ = sender.send(MsgGuiToZenoh::Close);
-                        self.sender_to_zenoh = None;
+                page_scout::Event::NewConfig(c) => {
+                    let (name, locator) = *c;
+                    match self.p_session.add_locator_config(name, locator) {
+                        Ok(_) => {
+                            self.selected_page = Page::Session;
+                        }
+                        Err(e) => {
+                            self.p_scout.set_err(e);
+                        }
                     }
                 }
             }
         }
     }
 
+    // profile 为配置的 id, 0 时返回默认 session
+    fn session_sender(&self, profile: u64) -> Option<(u64, &Sender<MsgGuiToZenoh>)> {
+        let id = self.p_session.session_id(profile)?;
+        self.sessions.get(&id).map(|s| (id, &s.sender))
+    }
+
+    fn session_not_opened(&self, profile: u64) -> String {
+        if profile == 0 {
+            return "not connected".to_string();
+        }
+        match self.p_session.session_name(profile) {
+            Some(name) => format!("session \"{name}\" is not opened"),
+            None => "the session config was deleted".to_string(),
+        }
+    }
+
     fn processing_page_sub_events(&mut self) {
         while let Some(event) = self.p_sub.events.pop_front() {
             match event {
-                page_sub::Event::AddSub(event) => {
-                    if let Some(sender) = &self.sender_to_zenoh {
+                page_sub::Event::AddSub(profile, event) => {
+                    if let Some((session_id, sender)) = self.session_sender(profile) {
+                        let id = event.id;
                         let _ = sender.send(MsgGuiToZenoh::AddSubReq(event));
+                        let _ = self.sub_sessions.insert(id, session_id);
                     } else {
                         let id = (*event).id;
                         self.p_sub
-                            .processing_add_sub_res(id, Err("not connected".to_string()));
+                            .processing_add_sub_res(id, Err(self.session_not_opened(profile)));
                     }
                 }
                 page_sub::Event::DelSub(id) => {
-                    if let Some(sender) = &self.sender_to_zenoh {
-                        let _ = sender.send(MsgGuiToZenoh::DelSubReq(id));
+                    if let Some(session_id) = self.sub_sessions.remove(&id) {
+                        if let Some(session) = self.sessions.get(&session_id) {
+                            let _ = session.sender.send(MsgGuiToZenoh::DelSubReq(id));
+                        }
                     }
                 }
             }
@@ -422,28 +816,277 @@ impl HammerApp {
     }
 
     fn processing_page_put_events(&mut self) {
+        use crate::page_put::Event;
         while let Some(event) = self.p_put.events.pop_front() {
             match event {
-                crate::page_put::Event::Put(p) => {
-                    if let Some(sender) = &self.sender_to_zenoh {
+                Event::Put(profile, p) => match self.put_target_sender(p.id, profile) {
+                    Ok((_, sender)) => {
                         let _ = sender.send(MsgGuiToZenoh::PutReq(p));
                     }
+                    Err(e) => {
+                        self.p_put.processing_put_res((p.id, false, e));
+                    }
+                },
+                Event::DeclarePublisher(profile, d) => {
+                    // 声明时确定 session, 之后默认 session 改变也不影响这个 publisher
+                    if let Some((session_id, sender)) = self.session_sender(profile) {
+                        let id = d.id;
+                        let _ = sender.send(MsgGuiToZenoh::DeclarePublisherReq(d));
+                        let _ = self.publisher_sessions.insert(id, session_id);
+                    } else {
+                        self.p_put.processing_declare_publisher_res(
+                            d.id,
+                            Err(self.session_not_opened(profile)),
+                        );
+                    }
+                }
+                Event::UndeclarePublisher(id) => {
+                    if let Some(session_id) = self.publisher_sessions.remove(&id) {
+                        if let Some(session) = self.sessions.get(&session_id) {
+                            let _ = session
+                                .sender
+                                .send(MsgGuiToZenoh::UndeclarePublisherReq(id));
+                        }
+                    }
+                }
+                Event::StartPutTask(profile, d) => {
+                    match self.put_target_sender(d.put.id, profile) {
+                        Ok((session_id, sender)) => {
+                            let id = d.put.id;
+                            let _ = sender.send(MsgGuiToZenoh::PutTaskReq(d));
+                            let _ = self.put_sessions.insert(id, session_id);
+                        }
+                        Err(e) => {
+                            self.p_put.processing_put_res((d.put.id, false, e));
+                        }
+                    }
+                }
+                Event::PutTaskCtrl(id, ctrl) => {
+                    if let Some(sender) = self.put_session_sender(id) {
+                        let _ = sender.send(MsgGuiToZenoh::PutTaskCtrlReq(id, ctrl));
+                    }
                 }
             }
         }
     }
 
+    // 已声明 publisher 时发送到声明时的 session, session 已关闭时返回错误, 不使用其他 session
+    fn put_target_sender(
+        &self,
+        put_id: u64,
+        profile: u64,
+    ) -> Result<(u64, &Sender<MsgGuiToZenoh>), String> {
+        match self.publisher_sessions.get(&put_id) {
+            Some(session_id) => self
+                .sessions
+                .get(session_id)
+                .map(|s| (*session_id, &s.sender))
+                .ok_or_else(|| "the session of the declared publisher is closed".to_string()),
+            None => self
+                .session_sender(profile)
+                .ok_or_else(|| self.session_not_opened(profile)),
+        }
+    }
+
+    // 连续发送任务所在的 session
+    fn put_session_sender(&self, put_id: u64) -> Option<&Sender<MsgGuiToZenoh>> {
+        let session_id = self.put_sessions.get(&put_id)?;
+        self.sessions.get(session_id).map(|s| &s.sender)
+    }
+
+    // 只处理 get 项当前 query 所在 session 的消息, 之前的 query 迟到的消息被忽略
+    fn take_get_session(&mut self, get_id: u64, session_id: u64) -> bool {
+        if self.get_sessions.get(&get_id) != Some(&session_id) {
+            return false;
+        }
+        let _ = self.get_sessions.remove(&get_id);
+        true
+    }
+
     fn processing_page_get_events(&mut self) {
         while let Some(event) = self.p_get.events.pop_front() {
             match event {
-                crate::page_get::Event::Get(p) => {
-                    if let Some(sender) = &self.sender_to_zenoh {
+                crate::page_get::Event::Get(profile, p) => {
+                    // 已声明 querier 时发送到 querier 所在的 session
+                    let sender = match self.querier_sessions.get(&p.id) {
+                        Some(session_id) => self
+                            .sessions
+                            .get(session_id)
+                            .map(|s| (*session_id, &s.sender)),
+                        None => self.session_sender(profile),
+                    };
+                    if let Some((session_id, sender)) = sender {
+                        let id = p.id;
                         let _ = sender.send(MsgGuiToZenoh::GetReq(p));
+                        let _ = self.get_sessions.insert(id, session_id);
+                    } else {
+                        self.p_get
+                            .processing_get_err(p.id, self.session_not_opened(profile));
+                    }
+                }
+                crate::page_get::Event::Cancel(id) => {
+                    if let Some(session_id) = self.get_sessions.get(&id) {
+                        if let Some(session) = self.sessions.get(session_id) {
+                            let _ = session.sender.send(MsgGuiToZenoh::CancelGetReq(id));
+                        }
+                    }
+                }
+                crate::page_get::Event::DeclareQuerier(profile, d) => {
+                    if let Some((session_id, sender)) = self.session_sender(profile) {
+                        let id = d.id;
+                        let _ = sender.send(MsgGuiToZenoh::DeclareQuerierReq(d));
+                        let _ = self.querier_sessions.insert(id, session_id);
+                    } else {
+                        self.p_get.processing_declare_querier_res(
+                            d.id,
+                            Err(self.session_not_opened(profile)),
+                        );
                     }
                 }
+                crate::page_get::Event::UndeclareQuerier(id) => {
+                    if let Some(session_id) = self.querier_sessions.remove(&id) {
+                        if let Some(session) = self.sessions.get(&session_id) {
+                            let _ = session.sender.send(MsgGuiToZenoh::UndeclareQuerierReq(id));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn processing_page_queryable_events(&mut self) {
+        while let Some(event) = self.p_queryable.events.pop_front() {
+            match event {
+                page_queryable::Event::AddQueryable(event) => {
+                    if let Some((session_id, sender)) = self.session_sender(0) {
+                        let id = event.id;
+                        let _ = sender.send(MsgGuiToZenoh::AddQueryableReq(event));
+                        let _ = self.queryable_sessions.insert(id, session_id);
+                    } else {
+                        let id = event.id;
+                        self.p_queryable
+                            .processing_add_queryable_res(id, Err("not connected".to_string()));
+                    }
+                }
+                page_queryable::Event::DelQueryable(id) => {
+                    // 发送到声明 queryable 的 session
+                    if let Some(session_id) = self.queryable_sessions.remove(&id) {
+                        if let Some(session) = self.sessions.get(&session_id) {
+                            let _ = session.sender.send(MsgGuiToZenoh::DelQueryableReq(id));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn processing_page_liveliness_events(&mut self) {
+        while let Some(event) = self.p_liveliness.events.pop_front() {
+            // 撤销 token 和取消订阅发送到声明时的 session
+            let session_id = match &event {
+                page_liveliness::Event::UndeclareToken(id) => self.token_sessions.remove(id),
+                page_liveliness::Event::DelSub => self.liveliness_sub_session.take(),
+                _ => self.p_session.session_id(0),
+            };
+            let session =
+                session_id.and_then(|id| self.sessions.get(&id).map(|s| (id, s.sender.clone())));
+            let Some((session_id, sender)) = session else {
+                self.processing_page_liveliness_not_connected(event);
+                continue;
+            };
+            let msg = match event {
+                page_liveliness::Event::DeclareToken(d) => {
+                    let _ = self.token_sessions.insert(d.0, session_id);
+                    MsgGuiToZenoh::DeclareTokenReq(d)
+                }
+                page_liveliness::Event::UndeclareToken(id) => MsgGuiToZenoh::UndeclareTokenReq(id),
+                page_liveliness::Event::AddSub(d) => {
+                    self.liveliness_sub_session = Some(session_id);
+                    MsgGuiToZenoh::AddLivelinessSubReq(d)
+                }
+                page_liveliness::Event::DelSub => MsgGuiToZenoh::DelLivelinessSubReq,
+                page_liveliness::Event::Get(d) => {
+                    self.liveliness_get_session = Some(session_id);
+                    MsgGuiToZenoh::LivelinessGetReq(d)
+                }
+            };
+            let _ = sender.send(msg);
+        }
+    }
+
+    fn processing_page_liveliness_not_connected(&mut self, event: page_liveliness::Event) {
+        let e = Err("not connected".to_string());
+        match event {
+            page_liveliness::Event::DeclareToken(d) => {
+                self.p_liveliness.processing_declare_token_res(d.0, e);
+            }
+            page_liveliness::Event::UndeclareToken(id) => {
+                self.p_liveliness.processing_undeclare_token_res(id);
+            }
+            page_liveliness::Event::AddSub(_) => {
+                self.p_liveliness.processing_add_sub_res(e);
+            }
+            page_liveliness::Event::DelSub => {
+                self.p_liveliness.processing_del_sub_res();
+            }
+            page_liveliness::Event::Get(_) => {
+                self.p_liveliness.processing_get_finish(e);
             }
         }
     }
+
+    fn processing_page_bench_events(&mut self) {
+        while let Some(event) = self.p_bench.events.pop_front() {
+            match event {
+                page_bench::Event::Start(d) => {
+                    if let Some((session_id, sender)) = self.session_sender(0) {
+                        let _ = sender.send(MsgGuiToZenoh::BenchStartReq(d));
+                        self.bench_session = Some(session_id);
+                    } else {
+                        self.p_bench
+                            .processing_bench_finish(Err("not connected".to_string()));
+                    }
+                }
+                page_bench::Event::Stop => {
+                    // 发送到运行测试的 session
+                    if let Some(session_id) = self.bench_session {
+                        if let Some(session) = self.sessions.get(&session_id) {
+                            let _ = session.sender.send(MsgGuiToZenoh::BenchStopReq);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+// 取出 channel 中所有的消息, 返回的 bool 表示发送端已经关闭
+fn try_recv_all(receiver: &Receiver<MsgZenohToGui>) -> (Vec<MsgZenohToGui>, bool) {
+    let mut msgs = Vec::new();
+    loop {
+        match receiver.try_recv() {
+            Ok(m) => msgs.push(m),
+            Err(TryRecvError::Empty) => {
+                return (msgs, false);
+            }
+            Err(TryRecvError::Disconnected) => {
+                return (msgs, true);
+            }
+        }
+    }
+}
+
+// 移除并返回属于 session 的 item id
+fn take_session_items(map: &mut BTreeMap<u64, u64>, session_id: u64) -> Vec<u64> {
+    let ids: Vec<u64> = map
+        .iter()
+        .filter(|(_, v)| **v == session_id)
+        .map(|(k, _)| *k)
+        .collect();
+    for id in &ids {
+        let _ = map.remove(id);
+    }
+    ids
 }
 
 fn show_about_window(ctx: &Context, is_open: &mut bool) {
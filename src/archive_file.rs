@@ -3,8 +3,9 @@ use serde_json;
 use std::{fs, path::Path};
 
 use crate::{
-    page_get::ArchivePageGet, page_put::ArchivePagePut, page_session::ArchivePageSession,
-    page_sub::ArchivePageSub,
+    page_bench::ArchivePageBench, page_get::ArchivePageGet, page_liveliness::ArchivePageLiveliness,
+    page_put::ArchivePagePut, page_queryable::ArchivePageQueryable, page_scout::ArchivePageScout,
+    page_session::ArchivePageSession, page_sub::ArchivePageSub,
 };
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -13,6 +14,14 @@ pub struct ArchiveApp {
     pub page_sub: ArchivePageSub,
     pub page_put: ArchivePagePut,
     pub page_get: ArchivePageGet,
+    #[serde(default)]
+    pub page_queryable: ArchivePageQueryable,
+    #[serde(default)]
+    pub page_liveliness: ArchivePageLiveliness,
+    #[serde(default)]
+    pub page_bench: ArchivePageBench,
+    #[serde(default)]
+    pub page_scout: ArchivePageScout,
 }
 
 impl ArchiveApp {
@@ -78,3 +87,14 @@ fn app_file_write() {
         }
     }
 }
+
+#[test]
+fn app_file_from_str_missing_page() {
+    let s = r#"{
+        "page_session": { "config_files": [] },
+        "page_sub": { "subs": [] },
+        "page_put": { "puts": [] },
+        "page_get": { "gets": [] }
+    }"#;
+    assert!(ArchiveApp::from_str(s).is_ok());
+}
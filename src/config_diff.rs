@@ -0,0 +1,178 @@
+use eframe::egui::{Checkbox, Color32, ComboBox, Grid, RichText, ScrollArea, Ui};
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+
+struct DiffRow {
+    key: String,
+    left: Option<String>,
+    right: Option<String>,
+}
+
+// 对比两个配置展开后的 key
+pub struct ConfigDiff {
+    pub left: u64,
+    pub right: u64,
+    only_diff: bool,
+    rows: Vec<DiffRow>,
+    pub err_str: Option<String>,
+}
+
+impl Default for ConfigDiff {
+    fn default() -> Self {
+        ConfigDiff {
+            left: 0,
+            right: 0,
+            only_diff: true,
+            rows: Vec::new(),
+            err_str: None,
+        }
+    }
+}
+
+impl ConfigDiff {
+    pub fn compare(&mut self, left: &Value, right: &Value) {
+        let mut left_map = BTreeMap::new();
+        let mut right_map = BTreeMap::new();
+        flatten(String::new(), left, &mut left_map);
+        flatten(String::new(), right, &mut right_map);
+
+        let keys: BTreeSet<&String> = left_map.keys().chain(right_map.keys()).collect();
+        self.rows = keys
+            .into_iter()
+            .map(|k| DiffRow {
+                key: k.clone(),
+                left: left_map.get(k).cloned(),
+                right: right_map.get(k).cloned(),
+            })
+            .collect();
+        self.err_str = None;
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    // profiles 为 (id, name), 返回 true 表示需要重新对比
+    pub fn show(&mut self, ui: &mut Ui, profiles: &[(u64, String)]) -> bool {
+        let mut compare = false;
+        let name = |id: u64| -> &str {
+            profiles
+                .iter()
+                .find(|(i, _)| *i == id)
+                .map_or("-", |(_, n)| n.as_str())
+        };
+
+        ui.horizontal(|ui| {
+            for (salt, selected) in [
+                ("page_session_diff_left", &mut self.left),
+                ("page_session_diff_right", &mut self.right),
+            ] {
+                ComboBox::from_id_salt(salt)
+                    .selected_text(name(*selected))
+                    .show_ui(ui, |ui| {
+                        for (id, n) in profiles {
+                            if ui.selectable_value(selected, *id, n.as_str()).changed() {
+                                compare = true;
+                            }
+                        }
+                    });
+            }
+
+            if ui.button("compare").clicked() {
+                compare = true;
+            }
+
+            ui.add(Checkbox::new(&mut self.only_diff, "only differences"));
+        });
+
+        if let Some(e) = &self.err_str {
+            ui.label(RichText::new(e).color(Color32::RED));
+        }
+
+        let diff_count = self.rows.iter().filter(|r| r.left != r.right).count();
+        ui.label(format!(
+            "keys: {}, different: {}",
+            self.rows.len(),
+            diff_count
+        ));
+
+        ui.add_space(4.0);
+
+        let highlight = Color32::from_rgb(220, 140, 0);
+        ScrollArea::both()
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                Grid::new("page_session_diff_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(RichText::new("key").strong());
+                        ui.label(RichText::new(name(self.left)).strong());
+                        ui.label(RichText::new(name(self.right)).strong());
+                        ui.end_row();
+
+                        for row in &self.rows {
+                            let differ = row.left != row.right;
+                            if self.only_diff && !differ {
+                                continue;
+                            }
+                            let text = |s: &str| {
+                                let t = RichText::new(s).monospace();
+                                if differ {
+                                    t.color(highlight)
+                                } else {
+                                    t
+                                }
+                            };
+                            ui.label(text(row.key.as_str()));
+                            ui.label(text(row.left.as_deref().unwrap_or("-")));
+                            ui.label(text(row.right.as_deref().unwrap_or("-")));
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        compare
+    }
+}
+
+// 按 zenoh 配置的路径格式展开, 数组和空 object 作为一个值
+fn flatten(prefix: String, value: &Value, out: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(o) if !o.is_empty() => {
+            for (k, v) in o {
+                let key = if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{prefix}/{k}")
+                };
+                flatten(key, v, out);
+            }
+        }
+        _ => {
+            let _ = out.insert(prefix, value.to_string());
+        }
+    }
+}
+
+#[test]
+fn flatten_path() {
+    let value = serde_json::json!({
+        "mode": "peer",
+        "connect": { "endpoints": ["tcp/127.0.0.1:7447"] },
+        "transport": { "link": { "tls": { "enable_mtls": false } } },
+        "plugins": {},
+    });
+    let mut out = BTreeMap::new();
+    flatten(String::new(), &value, &mut out);
+    let expected: BTreeMap<String, String> = [
+        ("connect/endpoints", r#"["tcp/127.0.0.1:7447"]"#),
+        ("mode", r#""peer""#),
+        ("plugins", "{}"),
+        ("transport/link/tls/enable_mtls", "false"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    assert_eq!(out, expected);
+}
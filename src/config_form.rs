@@ -0,0 +1,232 @@
+use eframe::egui::{Button, Checkbox, DragValue, Grid, RichText, TextEdit, TextStyle, Ui, Widget};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
+
+const MB: u64 = 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+enum FormMode {
+    #[default]
+    Peer,
+    Client,
+    Router,
+}
+
+// 常用的 session 配置, 不需要配置文件
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConfigForm {
+    mode: FormMode,
+    connect: Vec<String>,
+    listen: Vec<String>,
+    multicast_scouting: bool,
+    timestamping: bool,
+    shm_enabled: bool,
+    shm_pool_size: u64, // MB
+}
+
+impl Default for ConfigForm {
+    fn default() -> Self {
+        ConfigForm {
+            mode: FormMode::Peer,
+            connect: Vec::new(),
+            listen: Vec::new(),
+            multicast_scouting: true,
+            timestamping: false,
+            shm_enabled: true,
+            shm_pool_size: 16,
+        }
+    }
+}
+
+impl ConfigForm {
+    // 从已有的配置中读取表单包含的字段, 其他字段会被忽略
+    pub fn from_value(value: &Value) -> Self {
+        let mut form = ConfigForm::default();
+        if let Some(mode) = value["mode"].as_str() {
+            form.mode = match mode {
+                "client" => FormMode::Client,
+                "router" => FormMode::Router,
+                _ => FormMode::Peer,
+            };
+        }
+        let mode = form.mode.as_ref();
+        form.connect = endpoints(&value["connect"]["endpoints"], mode);
+        form.listen = endpoints(&value["listen"]["endpoints"], mode);
+        if let Some(b) = value["scouting"]["multicast"]["enabled"].as_bool() {
+            form.multicast_scouting = b;
+        }
+        // timestamping 可能按 mode 区分
+        let timestamping = &value["timestamping"]["enabled"];
+        if let Some(b) = timestamping
+            .as_bool()
+            .or_else(|| timestamping[mode].as_bool())
+        {
+            form.timestamping = b;
+        }
+        let shm = &value["transport"]["shared_memory"];
+        if let Some(b) = shm["enabled"].as_bool() {
+            form.shm_enabled = b;
+        }
+        if let Some(n) = shm["transport_optimization"]["pool_size"].as_u64() {
+            form.shm_pool_size = n.div_ceil(MB).max(1);
+        }
+        form
+    }
+
+    pub fn to_json5(&self) -> String {
+        let mut value = json!({
+            "mode": self.mode.as_ref(),
+            "scouting": {
+                "multicast": {
+                    "enabled": self.multicast_scouting,
+                },
+            },
+            "timestamping": {
+                "enabled": self.timestamping,
+            },
+            "transport": {
+                "shared_memory": {
+                    "enabled": self.shm_enabled,
+                    "transport_optimization": {
+                        "pool_size": self.shm_pool_size * MB,
+                    },
+                },
+            },
+        });
+        // 没有填写 endpoint 时使用 zenoh 的默认值
+        let list = |v: &Vec<String>| -> Vec<String> {
+            v.iter()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        };
+        let connect = list(&self.connect);
+        if !connect.is_empty() {
+            value["connect"] = json!({ "endpoints": connect });
+        }
+        let listen = list(&self.listen);
+        if !listen.is_empty() {
+            value["listen"] = json!({ "endpoints": listen });
+        }
+        serde_json::to_string_pretty(&value).unwrap_or_default()
+    }
+
+    // 返回 true 表示表单被修改
+    pub fn show(&mut self, ui: &mut Ui, enabled: bool) -> bool {
+        let mut changed = false;
+        Grid::new("page_session_config_form_grid")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("mode");
+                ui.horizontal(|ui| {
+                    for mode in FormMode::iter() {
+                        ui.add_enabled_ui(enabled, |ui| {
+                            if ui
+                                .selectable_value(&mut self.mode, mode, mode.as_ref())
+                                .changed()
+                            {
+                                changed = true;
+                            }
+                        });
+                    }
+                });
+                ui.end_row();
+
+                ui.label("connect");
+                changed |= show_endpoints(ui, "connect", &mut self.connect, enabled);
+                ui.end_row();
+
+                ui.label("listen");
+                changed |= show_endpoints(ui, "listen", &mut self.listen, enabled);
+                ui.end_row();
+
+                ui.label("multicast scouting");
+                changed |= ui
+                    .add_enabled(
+                        enabled,
+                        Checkbox::without_text(&mut self.multicast_scouting),
+                    )
+                    .changed();
+                ui.end_row();
+
+                ui.label("timestamping");
+                changed |= ui
+                    .add_enabled(enabled, Checkbox::without_text(&mut self.timestamping))
+                    .changed();
+                ui.end_row();
+
+                ui.label("shared memory");
+                ui.horizontal(|ui| {
+                    changed |= ui
+                        .add_enabled(enabled, Checkbox::without_text(&mut self.shm_enabled))
+                        .changed();
+                    let dv = DragValue::new(&mut self.shm_pool_size)
+                        .prefix("pool size: ")
+                        .suffix("MB")
+                        .speed(1.0)
+                        .range(1..=4096);
+                    changed |= ui.add_enabled(enabled && self.shm_enabled, dv).changed();
+                });
+                ui.end_row();
+            });
+        changed
+    }
+}
+
+// endpoints 可能按 mode 区分, 此时为 object
+fn endpoints(value: &Value, mode: &str) -> Vec<String> {
+    let a = match value {
+        Value::Array(a) => a,
+        Value::Object(o) => match o.get(mode) {
+            Some(Value::Array(a)) => a,
+            _ => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+    a.iter()
+        .filter_map(|v| v.as_str())
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn show_endpoints(ui: &mut Ui, id: &str, list: &mut Vec<String>, enabled: bool) -> bool {
+    let mut changed = false;
+    ui.vertical(|ui| {
+        let mut del_index = None;
+        for (i, s) in list.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(enabled, Button::new(RichText::new(" - ").code()))
+                    .clicked()
+                {
+                    del_index = Some(i);
+                }
+                changed |= TextEdit::singleline(s)
+                    .id_salt((id, i))
+                    .desired_width(3000.0)
+                    .font(TextStyle::Monospace)
+                    .hint_text("tcp/127.0.0.1:7447")
+                    .interactive(enabled)
+                    .ui(ui)
+                    .changed();
+            });
+        }
+        if let Some(i) = del_index {
+            list.remove(i);
+            changed = true;
+        }
+        if ui
+            .add_enabled(enabled, Button::new(RichText::new(" + ").code()))
+            .clicked()
+        {
+            list.push(String::new());
+            changed = true;
+        }
+    });
+    changed
+}
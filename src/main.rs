@@ -1,15 +1,23 @@
 mod app;
 mod archive_file;
+mod config_diff;
+mod config_form;
 mod data_viewer;
 mod hex_viewer;
 mod language;
+mod page_bench;
 mod page_get;
+mod page_liveliness;
 mod page_put;
+mod page_queryable;
+mod page_scout;
 mod page_session;
 mod page_sub;
 mod payload_editor;
+mod query_viewer;
 mod reply_viewer;
 mod sample_viewer;
+mod security_wizard;
 mod task_zenoh;
 mod zenoh_data;
 
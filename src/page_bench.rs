@@ -0,0 +1,514 @@
+use eframe::egui::{
+    Align, CentralPanel, Checkbox, CollapsingHeader, Color32, ComboBox, Context, DragValue, Grid,
+    Layout, RichText, ScrollArea, TextEdit, TextStyle, Ui, Widget,
+};
+use egui_plot::{Bar, BarChart, Legend, Line, Plot, PlotPoints};
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, str::FromStr, time::Duration};
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
+use zenoh::key_expr::OwnedKeyExpr;
+
+use crate::{
+    task_zenoh::{BenchData, BenchMode, BenchReport},
+    zenoh_data::{ZCongestionControl, ZPriority},
+};
+
+const RTT_BUFFER_SIZE: usize = 100000;
+const RATE_BUFFER_SIZE: usize = 1200;
+const HISTOGRAM_BUCKETS: usize = 50;
+
+pub enum Event {
+    Start(Box<BenchData>),
+    Stop,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+enum BenchRole {
+    #[default]
+    Ping,
+    Pong,
+    ThrPub,
+    ThrSub,
+}
+
+impl From<BenchRole> for BenchMode {
+    fn from(value: BenchRole) -> Self {
+        match value {
+            BenchRole::Ping => BenchMode::Ping,
+            BenchRole::Pong => BenchMode::Pong,
+            BenchRole::ThrPub => BenchMode::ThrPub,
+            BenchRole::ThrSub => BenchMode::ThrSub,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ArchivePageBench {
+    role: BenchRole,
+    ping_key: String,
+    pong_key: String,
+    thr_key: String,
+    payload_size: usize,
+    congestion_control: ZCongestionControl,
+    priority: ZPriority,
+    express: bool,
+    ping_count: u64,
+    ping_interval: u64,
+}
+
+impl Default for ArchivePageBench {
+    fn default() -> Self {
+        (&PageBench::default()).into()
+    }
+}
+
+// 往返时间的统计结果, 单位 us
+#[derive(Debug, PartialEq)]
+struct LatencyStats {
+    min: f64,
+    max: f64,
+    mean: f64,
+    p50: f64,
+    p90: f64,
+    p99: f64,
+    histogram: Vec<(f64, usize)>, // (bucket center, count)
+    bucket_width: f64,
+}
+
+impl LatencyStats {
+    fn new(rtts: &[f64]) -> Option<Self> {
+        if rtts.is_empty() {
+            return None;
+        }
+        let mut sorted = rtts.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let percentile = |p: f64| -> f64 {
+            let i = ((sorted.len() - 1) as f64 * p).round() as usize;
+            sorted[i]
+        };
+        let min = sorted[0];
+        let max = sorted[sorted.len() - 1];
+        let mean = sorted.iter().sum::<f64>() / sorted.len() as f64;
+
+        let bucket_width = ((max - min) / HISTOGRAM_BUCKETS as f64).max(1.0);
+        let mut counts = vec![0usize; HISTOGRAM_BUCKETS];
+        for v in sorted.iter() {
+            let i = (((v - min) / bucket_width) as usize).min(HISTOGRAM_BUCKETS - 1);
+            counts[i] += 1;
+        }
+        let histogram = counts
+            .into_iter()
+            .enumerate()
+            .map(|(i, c)| (min + bucket_width * (i as f64 + 0.5), c))
+            .collect();
+
+        Some(LatencyStats {
+            min,
+            max,
+            mean,
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+            histogram,
+            bucket_width,
+        })
+    }
+}
+
+pub struct PageBench {
+    pub events: VecDeque<Event>,
+    role: BenchRole,
+    ping_key: String,
+    pong_key: String,
+    thr_key: String,
+    payload_size: usize,
+    congestion_control: ZCongestionControl,
+    priority: ZPriority,
+    express: bool,
+    ping_count: u64,
+    ping_interval: u64,
+    running: bool,
+    err_str: Option<String>,
+    rtts: Vec<f64>, // us
+    lost: u64,
+    latency_stats: Option<LatencyStats>,
+    rate_time: f64,       // s
+    rates: Vec<[f64; 3]>, // (time s, msgs/s, bytes/s)
+}
+
+impl Default for PageBench {
+    fn default() -> Self {
+        PageBench {
+            events: VecDeque::new(),
+            role: BenchRole::Ping,
+            ping_key: "test/ping".to_string(),
+            pong_key: "test/pong".to_string(),
+            thr_key: "test/thr".to_string(),
+            payload_size: 8,
+            congestion_control: ZCongestionControl::Block,
+            priority: ZPriority::Data,
+            express: true,
+            ping_count: 1000,
+            ping_interval: 0,
+            running: false,
+            err_str: None,
+            rtts: Vec::new(),
+            lost: 0,
+            latency_stats: None,
+            rate_time: 0.0,
+            rates: Vec::new(),
+        }
+    }
+}
+
+impl From<&PageBench> for ArchivePageBench {
+    fn from(value: &PageBench) -> Self {
+        ArchivePageBench {
+            role: value.role,
+            ping_key: value.ping_key.clone(),
+            pong_key: value.pong_key.clone(),
+            thr_key: value.thr_key.clone(),
+            payload_size: value.payload_size,
+            congestion_control: value.congestion_control,
+            priority: value.priority,
+            express: value.express,
+            ping_count: value.ping_count,
+            ping_interval: value.ping_interval,
+        }
+    }
+}
+
+impl PageBench {
+    pub fn load(&mut self, archive: ArchivePageBench) -> Result<(), String> {
+        self.role = archive.role;
+        self.ping_key = archive.ping_key;
+        self.pong_key = archive.pong_key;
+        self.thr_key = archive.thr_key;
+        self.payload_size = archive.payload_size;
+        self.congestion_control = archive.congestion_control;
+        self.priority = archive.priority;
+        self.express = archive.express;
+        self.ping_count = archive.ping_count;
+        self.ping_interval = archive.ping_interval;
+        Ok(())
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        CentralPanel::default().show(ctx, |ui| {
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    self.show_options(ui);
+                    ui.separator();
+                    match self.role {
+                        BenchRole::Ping => self.show_latency(ui),
+                        BenchRole::Pong | BenchRole::ThrPub | BenchRole::ThrSub => {
+                            self.show_rate(ui)
+                        }
+                    }
+                });
+        });
+    }
+
+    fn show_options(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            for role in BenchRole::iter() {
+                ui.add_enabled_ui(!self.running, |ui| {
+                    ui.selectable_value(&mut self.role, role, role.as_ref());
+                });
+            }
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if self.running {
+                    if ui.button("stop").clicked() {
+                        self.events.push_back(Event::Stop);
+                    }
+                    ui.spinner();
+                } else if ui.button("start").clicked() {
+                    self.start();
+                }
+            });
+        });
+
+        if let Some(e) = &self.err_str {
+            ui.label(RichText::new(e).color(Color32::RED));
+        }
+
+        let running = self.running;
+        let role = self.role;
+        let mut show_grid = |ui: &mut Ui| {
+            let key_edit = |ui: &mut Ui, s: &mut String| {
+                TextEdit::singleline(s)
+                    .desired_width(3000.0)
+                    .font(TextStyle::Monospace)
+                    .interactive(!running)
+                    .ui(ui);
+            };
+            match role {
+                BenchRole::Ping | BenchRole::Pong => {
+                    ui.label("ping key:");
+                    key_edit(ui, &mut self.ping_key);
+                    ui.end_row();
+
+                    ui.label("pong key:");
+                    key_edit(ui, &mut self.pong_key);
+                    ui.end_row();
+                }
+                BenchRole::ThrPub | BenchRole::ThrSub => {
+                    ui.label("key:");
+                    key_edit(ui, &mut self.thr_key);
+                    ui.end_row();
+                }
+            }
+
+            if role == BenchRole::ThrSub {
+                return;
+            }
+
+            if role != BenchRole::Pong {
+                ui.label("payload size:");
+                let dv = DragValue::new(&mut self.payload_size)
+                    .suffix("B")
+                    .speed(1.0)
+                    .range(0..=64 * 1024 * 1024);
+                ui.add_enabled(!running, dv);
+                ui.end_row();
+            }
+
+            ui.label("congestion control:");
+            ui.add_enabled_ui(!running, |ui| {
+                ComboBox::new("bench congestion control", "")
+                    .selected_text(self.congestion_control.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ZCongestionControl::iter() {
+                            ui.selectable_value(
+                                &mut self.congestion_control,
+                                option,
+                                option.as_ref(),
+                            );
+                        }
+                    });
+            });
+            ui.end_row();
+
+            ui.label("priority:");
+            ui.add_enabled_ui(!running, |ui| {
+                ComboBox::new("bench priority", "")
+                    .selected_text(self.priority.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ZPriority::iter() {
+                            ui.selectable_value(&mut self.priority, option, option.as_ref());
+                        }
+                    });
+            });
+            ui.end_row();
+
+            ui.label("express:");
+            ui.add_enabled(!running, Checkbox::without_text(&mut self.express));
+            ui.end_row();
+
+            if role == BenchRole::Ping {
+                ui.label("count:");
+                let dv = DragValue::new(&mut self.ping_count)
+                    .speed(1.0)
+                    .range(1..=RTT_BUFFER_SIZE as u64);
+                ui.add_enabled(!running, dv);
+                ui.end_row();
+
+                ui.label("interval:");
+                let dv = DragValue::new(&mut self.ping_interval)
+                    .suffix("ms")
+                    .speed(1.0)
+                    .range(0..=10000);
+                ui.add_enabled(!running, dv);
+                ui.end_row();
+            }
+        };
+
+        CollapsingHeader::new("Options")
+            .default_open(true)
+            .show(ui, |ui| {
+                Grid::new("page_bench_options_grid")
+                    .num_columns(2)
+                    .striped(false)
+                    .show(ui, |ui| {
+                        show_grid(ui);
+                    });
+            });
+    }
+
+    fn show_latency(&mut self, ui: &mut Ui) {
+        let stats = match &self.latency_stats {
+            None => {
+                ui.label(format!("samples: 0, lost: {}", self.lost));
+                return;
+            }
+            Some(o) => o,
+        };
+
+        ui.label(format!("samples: {}, lost: {}", self.rtts.len(), self.lost));
+        Grid::new("page_bench_latency_grid")
+            .num_columns(6)
+            .striped(true)
+            .show(ui, |ui| {
+                for s in ["min", "mean", "p50", "p90", "p99", "max"] {
+                    ui.label(s);
+                }
+                ui.end_row();
+                for v in [
+                    stats.min, stats.mean, stats.p50, stats.p90, stats.p99, stats.max,
+                ] {
+                    ui.label(RichText::new(format!("{v:.1}us")).monospace());
+                }
+                ui.end_row();
+            });
+
+        ui.add_space(6.0);
+        ui.label("round trip time histogram:");
+        let bars: Vec<Bar> = stats
+            .histogram
+            .iter()
+            .map(|(x, c)| Bar::new(*x, *c as f64).width(stats.bucket_width))
+            .collect();
+        Plot::new("page_bench_latency_plot")
+            .height(300.0)
+            .x_axis_label("us")
+            .y_axis_label("count")
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                plot_ui.bar_chart(BarChart::new("rtt", bars));
+            });
+    }
+
+    fn show_rate(&mut self, ui: &mut Ui) {
+        if let Some([_, msgs, bytes]) = self.rates.last() {
+            ui.label(
+                RichText::new(format!("{:.1} msgs/s, {}/s", msgs, human_bytes(*bytes))).monospace(),
+            );
+        }
+
+        let msgs_points: PlotPoints = self.rates.iter().map(|r| [r[0], r[1]]).collect();
+        let bytes_points: PlotPoints = self.rates.iter().map(|r| [r[0], r[2]]).collect();
+
+        ui.label("msgs/s:");
+        Plot::new("page_bench_msgs_plot")
+            .height(200.0)
+            .x_axis_label("s")
+            .legend(Legend::default())
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("msgs/s", msgs_points));
+            });
+
+        ui.label("bytes/s:");
+        Plot::new("page_bench_bytes_plot")
+            .height(200.0)
+            .x_axis_label("s")
+            .legend(Legend::default())
+            .allow_scroll(false)
+            .show(ui, |plot_ui| {
+                plot_ui.line(Line::new("bytes/s", bytes_points));
+            });
+    }
+
+    fn start(&mut self) {
+        let parse = |s: &str| -> Result<OwnedKeyExpr, String> {
+            let key_str = s.replace([' ', '\t', '\n', '\r'], "");
+            OwnedKeyExpr::from_str(key_str.as_str()).map_err(|e| e.to_string())
+        };
+        let keys = parse(self.ping_key.as_str()).and_then(|ping_key| {
+            let pong_key = parse(self.pong_key.as_str())?;
+            let thr_key = parse(self.thr_key.as_str())?;
+            Ok((ping_key, pong_key, thr_key))
+        });
+        let (ping_key, pong_key, thr_key) = match keys {
+            Ok(o) => o,
+            Err(e) => {
+                self.err_str = Some(e);
+                return;
+            }
+        };
+
+        let d = BenchData {
+            mode: self.role.into(),
+            ping_key,
+            pong_key,
+            thr_key,
+            payload_size: self.payload_size,
+            congestion_control: self.congestion_control.into(),
+            priority: self.priority.into(),
+            express: self.express,
+            ping_count: self.ping_count,
+            ping_interval: Duration::from_millis(self.ping_interval),
+        };
+        self.events.push_back(Event::Start(Box::new(d)));
+        self.err_str = None;
+        self.running = true;
+        self.rtts.clear();
+        self.lost = 0;
+        self.latency_stats = None;
+        self.rate_time = 0.0;
+        self.rates.clear();
+    }
+
+    pub fn processing_bench_report(&mut self, report: BenchReport) {
+        match report {
+            BenchReport::Rtt(rtts, lost) => {
+                self.lost += lost;
+                if rtts.is_empty() {
+                    return;
+                }
+                let n = RTT_BUFFER_SIZE.saturating_sub(self.rtts.len());
+                self.rtts
+                    .extend(rtts.iter().take(n).map(|d| d.as_secs_f64() * 1000000.0));
+                self.latency_stats = LatencyStats::new(self.rtts.as_slice());
+            }
+            BenchReport::Rate(period, msgs, bytes) => {
+                let secs = period.as_secs_f64();
+                if secs <= 0.0 {
+                    return;
+                }
+                self.rate_time += secs;
+                if self.rates.len() >= RATE_BUFFER_SIZE {
+                    let _ = self.rates.remove(0);
+                }
+                self.rates
+                    .push([self.rate_time, msgs as f64 / secs, bytes as f64 / secs]);
+            }
+        }
+    }
+
+    pub fn processing_bench_finish(&mut self, r: Result<(), String>) {
+        self.running = false;
+        if let Err(e) = r {
+            self.err_str = Some(e);
+        }
+    }
+}
+
+fn human_bytes(v: f64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut v = v;
+    let mut i = 0;
+    while v >= 1024.0 && i < UNITS.len() - 1 {
+        v /= 1024.0;
+        i += 1;
+    }
+    format!("{v:.2}{}", UNITS[i])
+}
+
+#[test]
+fn latency_stats_percentile() {
+    let rtts: Vec<f64> = (1..=100).map(|v| v as f64).collect();
+    let stats = LatencyStats::new(rtts.as_slice()).unwrap();
+    assert_eq!(stats.min, 1.0);
+    assert_eq!(stats.max, 100.0);
+    assert_eq!(stats.mean, 50.5);
+    assert_eq!(stats.p50, 51.0);
+    assert_eq!(stats.p99, 99.0);
+    let total: usize = stats.histogram.iter().map(|(_, c)| c).sum();
+    assert_eq!(total, 100);
+    assert!(LatencyStats::new(&[]).is_none());
+}
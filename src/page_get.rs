@@ -1,12 +1,14 @@
 use crate::{
+    page_put::{matching_label, shm_status_label},
+    page_session::{session_selector, SessionEntry},
     payload_editor::{ArchivePayloadEdit, PayloadEdit},
     reply_viewer::ReplyViewer,
-    task_zenoh::QueryData,
-    zenoh_data::{zenoh_value_abstract, ZConsolidation, ZLocality, ZQueryTarget},
+    task_zenoh::{QuerierData, QueryData, QueryEndReason, QueryFinish, ShmStatus},
+    zenoh_data::{zenoh_value_abstract, ZConsolidation, ZLocality, ZQueryTarget, ZReplyKeyExpr},
 };
 use eframe::egui::{
-    Align, CentralPanel, CollapsingHeader, Color32, ComboBox, Context, DragValue, Grid, Id, Layout,
-    RichText, ScrollArea, SidePanel, TextEdit, TextStyle, Ui, Widget, Window,
+    Align, Button, CentralPanel, CollapsingHeader, Color32, ComboBox, Context, DragValue, Grid, Id,
+    Layout, ProgressBar, RichText, ScrollArea, SidePanel, TextEdit, TextStyle, Ui, Widget, Window,
 };
 use egui_dnd::dnd;
 use egui_extras::{Column, TableBody, TableBuilder, TableRow};
@@ -14,7 +16,7 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, VecDeque},
     str::FromStr,
-    time::Duration,
+    time::{Duration, Instant},
 };
 use strum::IntoEnumIterator;
 use zenoh::bytes::Encoding;
@@ -23,7 +25,17 @@ use zenoh::{bytes::ZBytes, key_expr::OwnedKeyExpr, query::Reply};
 
 // query
 pub enum Event {
-    Get(Box<QueryData>),
+    Get(u64, Box<QueryData>),              // session profile id, query
+    Cancel(u64),                           // get id
+    DeclareQuerier(u64, Box<QuerierData>), // session profile id, querier
+    UndeclareQuerier(u64),                 // get id
+}
+
+enum QueryState {
+    Idle,
+    Pending(Instant, Duration), // (start, timeout)
+    Finished(QueryFinish),
+    Failed(String), // query 没有发出
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -36,7 +48,13 @@ pub struct ArchivePageGetData {
     consolidation: ZConsolidation,
     locality: ZLocality,
     timeout: u64,
+    #[serde(default)]
+    accept_replies: ZReplyKeyExpr,
     payload: bool,
+    #[serde(default)]
+    shm: bool,
+    #[serde(default)]
+    session_id: u64, // 配置的 id, 0 表示默认 session
     archive_payload_edit: ArchivePayloadEdit,
 }
 
@@ -55,10 +73,16 @@ pub struct PageGetData {
     selected_consolidation: ZConsolidation,
     selected_locality: ZLocality,
     timeout: u64,
+    accept_replies: ZReplyKeyExpr,
     payload: bool,
+    shm: bool,
+    session: u64, // 配置的 id, 0 表示默认 session
     payload_edit: PayloadEdit,
     replies: Vec<Reply>,
     error_info: Option<RichText>,
+    query_state: QueryState,
+    declared: bool, // 已声明 querier
+    matching: bool,
 }
 
 impl Default for PageGetData {
@@ -73,10 +97,16 @@ impl Default for PageGetData {
             selected_consolidation: ZConsolidation::Auto,
             selected_locality: ZLocality::Any,
             timeout: 10000,
+            accept_replies: ZReplyKeyExpr::MatchingQuery,
             payload: false,
+            shm: false,
+            session: 0,
             payload_edit: PayloadEdit::default(),
             replies: Vec::new(),
             error_info: None,
+            query_state: QueryState::Idle,
+            declared: false,
+            matching: false,
         }
     }
 }
@@ -93,10 +123,16 @@ impl From<&PageGetData> for PageGetData {
             selected_consolidation: value.selected_consolidation,
             selected_locality: value.selected_locality,
             timeout: value.timeout,
+            accept_replies: value.accept_replies,
             payload: value.payload,
+            shm: value.shm,
+            session: value.session,
             payload_edit: (&value.payload_edit).into(),
             replies: Vec::new(),
             error_info: None,
+            query_state: QueryState::Idle,
+            declared: false,
+            matching: false,
         }
     }
 }
@@ -112,7 +148,10 @@ impl From<&PageGetData> for ArchivePageGetData {
             consolidation: value.selected_consolidation,
             locality: value.selected_locality,
             timeout: value.timeout,
+            accept_replies: value.accept_replies,
             payload: value.payload,
+            shm: value.shm,
+            session_id: value.session,
             archive_payload_edit: (&value.payload_edit).into(),
         }
     }
@@ -132,10 +171,16 @@ impl TryFrom<&ArchivePageGetData> for PageGetData {
             selected_consolidation: value.consolidation,
             selected_locality: value.locality,
             timeout: value.timeout,
+            accept_replies: value.accept_replies,
             payload: value.payload,
+            shm: value.shm,
+            session: value.session_id,
             payload_edit: (&value.archive_payload_edit).try_into()?,
             replies: Vec::new(),
             error_info: None,
+            query_state: QueryState::Idle,
+            declared: false,
+            matching: false,
         })
     }
 }
@@ -154,10 +199,16 @@ impl TryFrom<ArchivePageGetData> for PageGetData {
             selected_consolidation: value.consolidation,
             selected_locality: value.locality,
             timeout: value.timeout,
+            accept_replies: value.accept_replies,
             payload: value.payload,
+            shm: value.shm,
+            session: value.session_id,
             payload_edit: (&value.archive_payload_edit).try_into()?,
             replies: Vec::new(),
             error_info: None,
+            query_state: QueryState::Idle,
+            declared: false,
+            matching: false,
         })
     }
 }
@@ -169,26 +220,54 @@ impl PageGetData {
         events: &mut VecDeque<Event>,
         show_window: &mut bool,
         reply_window: &mut ReplyViewer,
+        shm_status: Option<&ShmStatus>,
+        sessions: &[SessionEntry],
     ) {
-        self.show_name_key_parameters_attachment(ui, events);
+        self.show_name_key_parameters_attachment(ui, events, sessions);
 
         ScrollArea::horizontal()
             .auto_shrink([false, false])
             .show(ui, |ui| {
                 self.show_options(ui);
-                self.show_payload_edit(ui);
+                self.show_payload_edit(ui, shm_status);
                 ui.separator();
                 self.show_reply_table(ui, show_window, reply_window);
             });
     }
 
-    fn show_name_key_parameters_attachment(&mut self, ui: &mut Ui, events: &mut VecDeque<Event>) {
+    fn show_name_key_parameters_attachment(
+        &mut self,
+        ui: &mut Ui,
+        events: &mut VecDeque<Event>,
+        sessions: &[SessionEntry],
+    ) {
         let mut input_grid = |ui: &mut Ui| {
             ui.label("name:");
             ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
-                if ui.button("send").clicked() {
+                // 等待上一次 query 结束或取消后才能再次发送
+                let pending = matches!(self.query_state, QueryState::Pending(..));
+                if ui.add_enabled(!pending, Button::new("send")).clicked() {
                     self.send(events);
                 }
+                if pending && ui.button("cancel").clicked() {
+                    events.push_back(Event::Cancel(self.id));
+                }
+                if ui
+                    .selectable_label(self.declared, "querier")
+                    .on_hover_text("declare querier")
+                    .clicked()
+                {
+                    if self.declared {
+                        events.push_back(Event::UndeclareQuerier(self.id));
+                        self.declared = false;
+                        self.matching = false;
+                    } else {
+                        self.declare_querier(events);
+                    }
+                }
+                if self.declared {
+                    matching_label(ui, self.matching, "queryables");
+                }
                 TextEdit::singleline(&mut self.name)
                     .desired_width(3000.0)
                     .font(TextStyle::Monospace)
@@ -201,6 +280,7 @@ impl PageGetData {
                 .desired_rows(1)
                 .desired_width(3000.0)
                 .font(TextStyle::Monospace)
+                .interactive(!self.declared)
                 .ui(ui);
             ui.end_row();
 
@@ -218,6 +298,13 @@ impl PageGetData {
                 .font(TextStyle::Monospace)
                 .ui(ui);
             ui.end_row();
+
+            // querier 属于声明时的 session
+            ui.label("session:");
+            ui.add_enabled_ui(!self.declared, |ui| {
+                session_selector(ui, "page_get_session", &mut self.session, sessions);
+            });
+            ui.end_row();
         };
         Grid::new("input_grid")
             .num_columns(2)
@@ -225,42 +312,105 @@ impl PageGetData {
             .show(ui, |ui| {
                 input_grid(ui);
             });
+
+        self.show_query_state(ui);
+    }
+
+    fn show_query_state(&mut self, ui: &mut Ui) {
+        if let Some(e) = &self.error_info {
+            ui.label(e.clone());
+        }
+
+        let replies = self.replies.len();
+        match &self.query_state {
+            QueryState::Idle => {}
+            QueryState::Pending(start, timeout) => {
+                let elapsed = start.elapsed();
+                let progress = elapsed.as_secs_f32() / timeout.as_secs_f32().max(0.001);
+                let text = format!(
+                    "pending, {} replies, {:.1} s / {:.1} s",
+                    replies,
+                    elapsed.as_secs_f32(),
+                    timeout.as_secs_f32()
+                );
+                ProgressBar::new(progress.min(1.0))
+                    .text(text)
+                    .desired_width(300.0)
+                    .ui(ui);
+                ui.ctx().request_repaint_after(Duration::from_millis(100));
+            }
+            QueryState::Finished(finish) => {
+                let elapsed = format!("{:.3} s", finish.elapsed.as_secs_f64());
+                let text = match &finish.reason {
+                    QueryEndReason::Completed => RichText::new(format!(
+                        "finished, {} replies in {}",
+                        finish.replies, elapsed
+                    )),
+                    QueryEndReason::Timeout => RichText::new(format!(
+                        "timeout, {} replies in {}",
+                        finish.replies, elapsed
+                    ))
+                    .color(Color32::from_rgb(220, 140, 0)),
+                    QueryEndReason::Cancelled => RichText::new(format!(
+                        "cancelled, {} replies in {}",
+                        finish.replies, elapsed
+                    ))
+                    .color(Color32::GRAY),
+                };
+                ui.label(text);
+            }
+            QueryState::Failed(e) => {
+                ui.label(RichText::new(e).color(Color32::RED));
+            }
+        }
     }
 
     fn show_options(&mut self, ui: &mut Ui) {
+        // querier 的选项以声明时为准
+        let declared = self.declared;
         let mut show_grid = |ui: &mut Ui| {
             ui.label("target:");
-            ComboBox::new("query target", "")
-                .selected_text(self.selected_target.as_ref())
-                .show_ui(ui, |ui| {
-                    for option in ZQueryTarget::iter() {
-                        ui.selectable_value(&mut self.selected_target, option, option.as_ref());
-                    }
-                });
+            ui.add_enabled_ui(!declared, |ui| {
+                ComboBox::new("query target", "")
+                    .selected_text(self.selected_target.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ZQueryTarget::iter() {
+                            ui.selectable_value(&mut self.selected_target, option, option.as_ref());
+                        }
+                    });
+            });
             ui.end_row();
 
             ui.label("consolidation:");
-            ComboBox::new("consolidation", "")
-                .selected_text(self.selected_consolidation.as_ref())
-                .show_ui(ui, |ui| {
-                    for option in ZConsolidation::iter() {
-                        ui.selectable_value(
-                            &mut self.selected_consolidation,
-                            option,
-                            option.as_ref(),
-                        );
-                    }
-                });
+            ui.add_enabled_ui(!declared, |ui| {
+                ComboBox::new("consolidation", "")
+                    .selected_text(self.selected_consolidation.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ZConsolidation::iter() {
+                            ui.selectable_value(
+                                &mut self.selected_consolidation,
+                                option,
+                                option.as_ref(),
+                            );
+                        }
+                    });
+            });
             ui.end_row();
 
             ui.label("locality:");
-            ComboBox::new("locality", "")
-                .selected_text(self.selected_locality.as_ref())
-                .show_ui(ui, |ui| {
-                    for option in ZLocality::iter() {
-                        ui.selectable_value(&mut self.selected_locality, option, option.as_ref());
-                    }
-                });
+            ui.add_enabled_ui(!declared, |ui| {
+                ComboBox::new("locality", "")
+                    .selected_text(self.selected_locality.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ZLocality::iter() {
+                            ui.selectable_value(
+                                &mut self.selected_locality,
+                                option,
+                                option.as_ref(),
+                            );
+                        }
+                    });
+            });
             ui.end_row();
 
             ui.label("timeout:");
@@ -268,7 +418,20 @@ impl PageGetData {
                 .suffix("ms")
                 .speed(10.0)
                 .range(0..=10000);
-            ui.add(dv);
+            ui.add_enabled(!declared, dv);
+            ui.end_row();
+
+            ui.label("accept replies:")
+                .on_hover_text("accept replies on key expressions disjoint from the query");
+            ui.add_enabled_ui(!declared, |ui| {
+                ComboBox::new("accept replies", "")
+                    .selected_text(self.accept_replies.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ZReplyKeyExpr::iter() {
+                            ui.selectable_value(&mut self.accept_replies, option, option.as_ref());
+                        }
+                    });
+            });
             ui.end_row();
         };
 
@@ -284,11 +447,20 @@ impl PageGetData {
             });
     }
 
-    fn show_payload_edit(&mut self, ui: &mut Ui) {
+    fn show_payload_edit(&mut self, ui: &mut Ui, shm_status: Option<&ShmStatus>) {
         CollapsingHeader::new("Payload")
             .default_open(true)
             .show(ui, |ui| {
-                ui.checkbox(&mut self.payload, "payload");
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.payload, "payload");
+                    if self.payload {
+                        ui.checkbox(&mut self.shm, "shm")
+                            .on_hover_text("allocate payload from shm provider");
+                        if self.shm {
+                            shm_status_label(ui, shm_status);
+                        }
+                    }
+                });
 
                 if self.payload {
                     self.payload_edit.show(ui);
@@ -391,16 +563,43 @@ impl PageGetData {
         table.header(20.0, table_header).body(table_body);
     }
 
-    fn send(&mut self, events: &mut VecDeque<Event>) {
-        self.replies.clear();
+    fn key_expr(&mut self) -> Option<OwnedKeyExpr> {
         let key_str = self.input_key.replace(&[' ', '\t', '\n', '\r'], "");
-        let key_expr: OwnedKeyExpr = match OwnedKeyExpr::from_str(key_str.as_str()) {
-            Ok(o) => o,
+        match OwnedKeyExpr::from_str(key_str.as_str()) {
+            Ok(o) => Some(o),
             Err(e) => {
                 let rt = RichText::new(format!("{}", e)).color(Color32::RED);
                 self.error_info = Some(rt);
-                return;
+                None
             }
+        }
+    }
+
+    fn declare_querier(&mut self, events: &mut VecDeque<Event>) {
+        self.error_info = None;
+        let Some(key_expr) = self.key_expr() else {
+            return;
+        };
+        let d = QuerierData {
+            id: self.id,
+            key_expr,
+            target: self.selected_target.into(),
+            consolidation: self.selected_consolidation.into(),
+            locality: self.selected_locality.into(),
+            timeout: Duration::from_millis(self.timeout),
+            accept_replies: self.accept_replies.into(),
+        };
+        events.push_back(Event::DeclareQuerier(self.session, Box::new(d)));
+        self.declared = true;
+        self.matching = false;
+    }
+
+    fn send(&mut self, events: &mut VecDeque<Event>) {
+        self.replies.clear();
+        self.error_info = None;
+        self.query_state = QueryState::Idle;
+        let Some(key_expr) = self.key_expr() else {
+            return;
         };
 
         let parameters: Parameters = Parameters::from(self.input_parameters.clone());
@@ -426,9 +625,12 @@ impl PageGetData {
             consolidation: self.selected_consolidation.into(),
             locality: self.selected_locality.into(),
             timeout: Duration::from_millis(self.timeout),
+            accept_replies: self.accept_replies.into(),
             value,
+            shm: self.shm,
         };
-        events.push_back(Event::Get(Box::new(d)));
+        self.query_state = QueryState::Pending(Instant::now(), d.timeout);
+        events.push_back(Event::Get(self.session, Box::new(d)));
     }
 }
 
@@ -441,6 +643,7 @@ pub struct PageGet {
     reply_viewer_window: ReplyViewer,
     // dnd: DragDropUi,
     dnd_items: Vec<DndItem>,
+    shm_status: Option<ShmStatus>,
 }
 
 impl Default for PageGet {
@@ -454,6 +657,7 @@ impl Default for PageGet {
             reply_viewer_window: ReplyViewer::default(),
             // dnd: DragDropUi::default(),
             dnd_items: Vec::new(),
+            shm_status: None,
         };
         p.add_get_data(PageGetData::default());
         p
@@ -489,7 +693,7 @@ impl PageGet {
         Ok(())
     }
 
-    pub fn show(&mut self, ctx: &Context) {
+    pub fn show(&mut self, ctx: &Context, sessions: &[SessionEntry]) {
         SidePanel::left("page_get_panel_left")
             .resizable(true)
             .show(ctx, |ui| {
@@ -509,6 +713,8 @@ impl PageGet {
                 &mut self.events,
                 &mut self.show_reply_viewer_window,
                 &mut self.reply_viewer_window,
+                self.shm_status.as_ref(),
+                sessions,
             );
         });
 
@@ -583,7 +789,14 @@ impl PageGet {
             return;
         }
 
-        let _ = self.data_map.remove(&get_id);
+        if let Some(d) = self.data_map.remove(&get_id) {
+            if matches!(d.query_state, QueryState::Pending(..)) {
+                self.events.push_back(Event::Cancel(get_id));
+            }
+            if d.declared {
+                self.events.push_back(Event::UndeclareQuerier(get_id));
+            }
+        }
         let mut del_index = None;
         for (i, di) in self.dnd_items.iter().enumerate() {
             if di.key_id == get_id {
@@ -597,10 +810,22 @@ impl PageGet {
     }
 
     fn clean_all_get_data(&mut self) {
+        for (id, d) in &self.data_map {
+            if matches!(d.query_state, QueryState::Pending(..)) {
+                self.events.push_back(Event::Cancel(*id));
+            }
+            if d.declared {
+                self.events.push_back(Event::UndeclareQuerier(*id));
+            }
+        }
         self.data_map.clear();
         self.selected_data_id = 0;
     }
 
+    pub fn processing_shm_status(&mut self, status: ShmStatus) {
+        self.shm_status = Some(status);
+    }
+
     pub fn processing_get_res(&mut self, res: Box<(u64, Reply)>) {
         let (id, reply) = *res;
         if let Some(d) = self.data_map.get_mut(&id) {
@@ -608,6 +833,46 @@ impl PageGet {
             d.replies.push(reply);
         }
     }
+
+    pub fn processing_get_err(&mut self, id: u64, e: String) {
+        if let Some(d) = self.data_map.get_mut(&id) {
+            d.query_state = QueryState::Failed(e);
+        }
+    }
+
+    pub fn processing_get_finish(&mut self, id: u64, finish: QueryFinish) {
+        if let Some(d) = self.data_map.get_mut(&id) {
+            d.query_state = QueryState::Finished(finish);
+        }
+    }
+
+    pub fn processing_declare_querier_res(&mut self, id: u64, r: Result<(), String>) {
+        if let Some(d) = self.data_map.get_mut(&id) {
+            match r {
+                Ok(_) => {
+                    d.declared = true;
+                }
+                Err(e) => {
+                    d.error_info = Some(RichText::new(e).color(Color32::RED));
+                    d.declared = false;
+                    d.matching = false;
+                }
+            }
+        }
+    }
+
+    pub fn processing_undeclare_querier_res(&mut self, id: u64) {
+        if let Some(d) = self.data_map.get_mut(&id) {
+            d.declared = false;
+            d.matching = false;
+        }
+    }
+
+    pub fn processing_querier_matching_status(&mut self, id: u64, matching: bool) {
+        if let Some(d) = self.data_map.get_mut(&id) {
+            d.matching = matching;
+        }
+    }
 }
 
 #[derive(Hash)]
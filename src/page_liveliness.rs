@@ -0,0 +1,561 @@
+use eframe::egui::{
+    Align, CentralPanel, CollapsingHeader, Color32, Context, DragValue, Grid, Layout, RichText,
+    ScrollArea, TextEdit, TextStyle, Ui, Widget,
+};
+use egui_extras::{Column, TableBody, TableBuilder, TableRow};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
+use zenoh::{
+    key_expr::OwnedKeyExpr,
+    query::Reply,
+    sample::{Sample, SampleKind},
+};
+
+use crate::{
+    task_zenoh::{LivelinessGetData, LivelinessSubData},
+    zenoh_data::system_time_to_string,
+};
+
+const EVENT_BUFFER_SIZE: usize = 1000;
+
+pub enum Event {
+    DeclareToken(Box<(u64, OwnedKeyExpr)>), // (token id, key expr)
+    UndeclareToken(u64),                    // token id
+    AddSub(Box<LivelinessSubData>),
+    DelSub,
+    Get(Box<LivelinessGetData>),
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ArchivePageLivelinessToken {
+    key_expr: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ArchivePageLiveliness {
+    tokens: Vec<ArchivePageLivelinessToken>,
+    sub_key_expr: String,
+    sub_history: bool,
+    get_key_expr: String,
+    get_timeout: u64,
+}
+
+impl Default for ArchivePageLiveliness {
+    fn default() -> Self {
+        (&PageLiveliness::default()).into()
+    }
+}
+
+struct TokenData {
+    id: u64,
+    key_expr: String,
+    declared: bool,
+    err_str: Option<String>,
+}
+
+impl TokenData {
+    fn new(key_expr: String) -> Self {
+        TokenData {
+            id: 0,
+            key_expr,
+            declared: false,
+            err_str: None,
+        }
+    }
+}
+
+pub struct PageLiveliness {
+    pub events: VecDeque<Event>,
+    token_id_count: u64,
+    tokens: BTreeMap<u64, TokenData>,
+    sub_key_expr: String,
+    sub_history: bool,
+    subscribed: bool,
+    sub_err_str: Option<String>,
+    alive_tokens: BTreeMap<String, SystemTime>, // <key, join time>
+    sub_events: VecDeque<(SystemTime, SampleKind, String)>,
+    get_key_expr: String,
+    get_timeout: u64,
+    get_running: bool,
+    get_err_str: Option<String>,
+    get_replies: Vec<(Reply, SystemTime)>,
+}
+
+impl Default for PageLiveliness {
+    fn default() -> Self {
+        let mut p = PageLiveliness {
+            events: VecDeque::new(),
+            token_id_count: 0,
+            tokens: BTreeMap::new(),
+            sub_key_expr: "**".to_string(),
+            sub_history: true,
+            subscribed: false,
+            sub_err_str: None,
+            alive_tokens: BTreeMap::new(),
+            sub_events: VecDeque::new(),
+            get_key_expr: "**".to_string(),
+            get_timeout: 10000,
+            get_running: false,
+            get_err_str: None,
+            get_replies: Vec::new(),
+        };
+        p.add_token(TokenData::new("demo/hammer".to_string()));
+        p
+    }
+}
+
+impl From<&PageLiveliness> for ArchivePageLiveliness {
+    fn from(value: &PageLiveliness) -> Self {
+        ArchivePageLiveliness {
+            tokens: value
+                .tokens
+                .values()
+                .map(|t| ArchivePageLivelinessToken {
+                    key_expr: t.key_expr.clone(),
+                })
+                .collect(),
+            sub_key_expr: value.sub_key_expr.clone(),
+            sub_history: value.sub_history,
+            get_key_expr: value.get_key_expr.clone(),
+            get_timeout: value.get_timeout,
+        }
+    }
+}
+
+impl PageLiveliness {
+    pub fn load(&mut self, archive: ArchivePageLiveliness) -> Result<(), String> {
+        self.tokens.clear();
+        for t in archive.tokens {
+            self.add_token(TokenData::new(t.key_expr));
+        }
+        self.sub_key_expr = archive.sub_key_expr;
+        self.sub_history = archive.sub_history;
+        self.get_key_expr = archive.get_key_expr;
+        self.get_timeout = archive.get_timeout;
+        Ok(())
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        CentralPanel::default().show(ctx, |ui| {
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    self.show_tokens(ui);
+                    ui.separator();
+                    self.show_subscriber(ui);
+                    ui.separator();
+                    self.show_get(ui);
+                });
+        });
+    }
+
+    fn add_token(&mut self, mut token: TokenData) {
+        self.token_id_count += 1;
+        token.id = self.token_id_count;
+        self.tokens.insert(self.token_id_count, token);
+    }
+
+    fn show_tokens(&mut self, ui: &mut Ui) {
+        let mut del_token_id = None;
+
+        CollapsingHeader::new("Tokens")
+            .default_open(true)
+            .show(ui, |ui| {
+                if ui
+                    .button(RichText::new(" + ").code())
+                    .on_hover_text("add token")
+                    .clicked()
+                {
+                    self.add_token(TokenData::new("demo/hammer".to_string()));
+                }
+
+                Grid::new("page_liveliness_tokens")
+                    .num_columns(3)
+                    .show(ui, |ui| {
+                        for token in self.tokens.values_mut() {
+                            ui.add_enabled_ui(!token.declared, |ui| {
+                                if ui
+                                    .button(RichText::new(" - ").code())
+                                    .on_hover_text("del")
+                                    .clicked()
+                                {
+                                    del_token_id = Some(token.id);
+                                }
+                            });
+
+                            if ui.selectable_label(token.declared, "declare").clicked() {
+                                if token.declared {
+                                    self.events.push_back(Event::UndeclareToken(token.id));
+                                    token.declared = false;
+                                } else {
+                                    match parse_key_expr(token.key_expr.as_str()) {
+                                        Ok(key_expr) => {
+                                            let event =
+                                                Event::DeclareToken(Box::new((token.id, key_expr)));
+                                            self.events.push_back(event);
+                                            token.err_str = None;
+                                            token.declared = true;
+                                        }
+                                        Err(e) => {
+                                            token.err_str = Some(e);
+                                        }
+                                    }
+                                }
+                            }
+
+                            ui.horizontal(|ui| {
+                                TextEdit::singleline(&mut token.key_expr)
+                                    .desired_width(400.0)
+                                    .font(TextStyle::Monospace)
+                                    .interactive(!token.declared)
+                                    .ui(ui);
+
+                                if let Some(e) = &token.err_str {
+                                    ui.label(RichText::new(e).color(Color32::RED));
+                                }
+                            });
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(id) = del_token_id {
+            let _ = self.tokens.remove(&id);
+        }
+    }
+
+    fn show_subscriber(&mut self, ui: &mut Ui) {
+        CollapsingHeader::new("Subscriber")
+            .default_open(true)
+            .show(ui, |ui| {
+                Grid::new("page_liveliness_subscriber")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("key expr:");
+                        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                            if ui.selectable_label(self.subscribed, "subscribe").clicked() {
+                                self.switch_subscribe();
+                            }
+
+                            TextEdit::singleline(&mut self.sub_key_expr)
+                                .desired_width(3000.0)
+                                .font(TextStyle::Monospace)
+                                .interactive(!self.subscribed)
+                                .ui(ui);
+                        });
+                        ui.end_row();
+
+                        ui.label("history:");
+                        ui.add_enabled_ui(!self.subscribed, |ui| {
+                            ui.checkbox(&mut self.sub_history, "");
+                        });
+                        ui.end_row();
+                    });
+
+                if let Some(e) = &self.sub_err_str {
+                    ui.label(RichText::new(e).color(Color32::RED));
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(format!("alive tokens: {}", self.alive_tokens.len()));
+                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                        if ui.button("clean").on_hover_text("clean events").clicked() {
+                            self.sub_events.clear();
+                        }
+                    });
+                });
+
+                ui.push_id("page_liveliness_alive_tokens", |ui| {
+                    self.show_alive_tokens_table(ui);
+                });
+
+                ui.add_space(10.0);
+
+                ui.push_id("page_liveliness_sub_events", |ui| {
+                    self.show_sub_events_table(ui);
+                });
+            });
+    }
+
+    fn switch_subscribe(&mut self) {
+        if self.subscribed {
+            self.events.push_back(Event::DelSub);
+            self.subscribed = false;
+            return;
+        }
+
+        match parse_key_expr(self.sub_key_expr.as_str()) {
+            Ok(key_expr) => {
+                let d = LivelinessSubData {
+                    key_expr,
+                    history: self.sub_history,
+                };
+                self.events.push_back(Event::AddSub(Box::new(d)));
+                self.alive_tokens.clear();
+                self.sub_err_str = None;
+                self.subscribed = true;
+            }
+            Err(e) => {
+                self.sub_err_str = Some(e);
+            }
+        }
+    }
+
+    fn show_alive_tokens_table(&mut self, ui: &mut Ui) {
+        let table_header = |mut table_row: TableRow| {
+            table_row.col(|ui| {
+                ui.label("alive token");
+            });
+            table_row.col(|ui| {
+                ui.label("since");
+            });
+        };
+
+        let table_body = |mut body: TableBody| {
+            for (key, join_time) in &self.alive_tokens {
+                body.row(20.0, |mut row| {
+                    row.col(|ui| {
+                        ui.label(RichText::new(key).monospace());
+                    });
+                    row.col(|ui| {
+                        let text = system_time_to_string(*join_time);
+                        ui.label(RichText::new(text).size(12.0));
+                    });
+                });
+            }
+        };
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .max_scroll_height(200.0)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::initial(300.0).resizable(true).clip(true))
+            .column(Column::remainder())
+            .header(20.0, table_header)
+            .body(table_body);
+    }
+
+    fn show_sub_events_table(&mut self, ui: &mut Ui) {
+        let table_header = |mut table_row: TableRow| {
+            table_row.col(|ui| {
+                ui.label("time");
+            });
+            table_row.col(|ui| {
+                ui.label("event");
+            });
+            table_row.col(|ui| {
+                ui.label("key");
+            });
+        };
+
+        let table_body = |mut body: TableBody| {
+            for (t, kind, key) in self.sub_events.iter().rev() {
+                body.row(20.0, |mut row| {
+                    row.col(|ui| {
+                        let text = system_time_to_string(*t);
+                        ui.label(RichText::new(text).size(12.0));
+                    });
+                    row.col(|ui| {
+                        let text = match kind {
+                            SampleKind::Put => RichText::new("join").color(Color32::GREEN),
+                            SampleKind::Delete => RichText::new("leave").color(Color32::RED),
+                        };
+                        ui.label(text);
+                    });
+                    row.col(|ui| {
+                        ui.label(RichText::new(key).monospace());
+                    });
+                });
+            }
+        };
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .max_scroll_height(200.0)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::auto())
+            .column(Column::auto())
+            .column(Column::remainder())
+            .header(20.0, table_header)
+            .body(table_body);
+    }
+
+    fn show_get(&mut self, ui: &mut Ui) {
+        CollapsingHeader::new("Get")
+            .default_open(true)
+            .show(ui, |ui| {
+                Grid::new("page_liveliness_get")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("key expr:");
+                        ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                            if ui.button("get").clicked() {
+                                self.send_get();
+                            }
+                            if self.get_running {
+                                ui.spinner();
+                            }
+
+                            TextEdit::singleline(&mut self.get_key_expr)
+                                .desired_width(3000.0)
+                                .font(TextStyle::Monospace)
+                                .ui(ui);
+                        });
+                        ui.end_row();
+
+                        ui.label("timeout:");
+                        DragValue::new(&mut self.get_timeout)
+                            .suffix("ms")
+                            .speed(10.0)
+                            .range(0..=10000)
+                            .ui(ui);
+                        ui.end_row();
+                    });
+
+                if let Some(e) = &self.get_err_str {
+                    ui.label(RichText::new(e).color(Color32::RED));
+                }
+
+                ui.label(format!("replies count: {}", self.get_replies.len()));
+
+                ui.push_id("page_liveliness_get_replies", |ui| {
+                    self.show_get_replies_table(ui);
+                });
+            });
+    }
+
+    fn send_get(&mut self) {
+        match parse_key_expr(self.get_key_expr.as_str()) {
+            Ok(key_expr) => {
+                let d = LivelinessGetData {
+                    key_expr,
+                    timeout: Duration::from_millis(self.get_timeout),
+                };
+                self.events.push_back(Event::Get(Box::new(d)));
+                self.get_replies.clear();
+                self.get_err_str = None;
+                self.get_running = true;
+            }
+            Err(e) => {
+                self.get_err_str = Some(e);
+            }
+        }
+    }
+
+    fn show_get_replies_table(&mut self, ui: &mut Ui) {
+        let table_header = |mut table_row: TableRow| {
+            table_row.col(|ui| {
+                ui.label("token");
+            });
+            table_row.col(|ui| {
+                ui.label("time");
+            });
+        };
+
+        let table_body = |mut body: TableBody| {
+            for (reply, t) in &self.get_replies {
+                body.row(20.0, |mut row| {
+                    row.col(|ui| {
+                        let text = match reply.result() {
+                            Ok(sample) => RichText::new(sample.key_expr().as_str()).monospace(),
+                            Err(e) => {
+                                let s =
+                                    String::from_utf8_lossy(&e.payload().to_bytes()).to_string();
+                                RichText::new(s).color(Color32::RED)
+                            }
+                        };
+                        ui.label(text);
+                    });
+                    row.col(|ui| {
+                        let text = system_time_to_string(*t);
+                        ui.label(RichText::new(text).size(12.0));
+                    });
+                });
+            }
+        };
+
+        TableBuilder::new(ui)
+            .striped(true)
+            .max_scroll_height(200.0)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::initial(300.0).resizable(true).clip(true))
+            .column(Column::remainder())
+            .header(20.0, table_header)
+            .body(table_body);
+    }
+
+    pub fn processing_declare_token_res(&mut self, id: u64, r: Result<(), String>) {
+        if let Some(token) = self.tokens.get_mut(&id) {
+            match r {
+                Ok(_) => {
+                    token.err_str = None;
+                    token.declared = true;
+                }
+                Err(e) => {
+                    token.err_str = Some(e);
+                    token.declared = false;
+                }
+            }
+        }
+    }
+
+    pub fn processing_undeclare_token_res(&mut self, id: u64) {
+        if let Some(token) = self.tokens.get_mut(&id) {
+            token.declared = false;
+        }
+    }
+
+    pub fn processing_add_sub_res(&mut self, r: Result<(), String>) {
+        match r {
+            Ok(_) => {
+                self.sub_err_str = None;
+                self.subscribed = true;
+            }
+            Err(e) => {
+                self.sub_err_str = Some(e);
+                self.subscribed = false;
+            }
+        }
+    }
+
+    pub fn processing_del_sub_res(&mut self) {
+        self.subscribed = false;
+        self.alive_tokens.clear();
+    }
+
+    pub fn processing_sub_cb(&mut self, sample: Sample, receipt_time: SystemTime) {
+        let key = sample.key_expr().to_string();
+        match sample.kind() {
+            SampleKind::Put => {
+                let _ = self.alive_tokens.insert(key.clone(), receipt_time);
+            }
+            SampleKind::Delete => {
+                let _ = self.alive_tokens.remove(&key);
+            }
+        }
+
+        if self.sub_events.len() >= EVENT_BUFFER_SIZE {
+            let _ = self.sub_events.pop_front();
+        }
+        self.sub_events
+            .push_back((receipt_time, sample.kind(), key));
+    }
+
+    pub fn processing_get_res(&mut self, reply: Reply, receipt_time: SystemTime) {
+        self.get_replies.push((reply, receipt_time));
+    }
+
+    pub fn processing_get_finish(&mut self, r: Result<(), String>) {
+        self.get_running = false;
+        self.get_err_str = r.err();
+    }
+}
+
+fn parse_key_expr(s: &str) -> Result<OwnedKeyExpr, String> {
+    let key_str = s.replace([' ', '\t', '\n', '\r'], "");
+    OwnedKeyExpr::from_str(key_str.as_str()).map_err(|e| e.to_string())
+}
@@ -1,12 +1,16 @@
 use crate::{
-    payload_editor::{ArchivePayloadEdit, PayloadEdit},
-    task_zenoh::PutData,
-    zenoh_data::{ZCongestionControl, ZPriority},
+    page_session::{session_selector, SessionEntry},
+    payload_editor::{parse_str_to_vec, ArchivePayloadEdit, PayloadEdit},
+    task_zenoh::{
+        AdvancedPubData, PubMissDetection, PublisherData, PutData, PutTaskCtrl, PutTaskData,
+        PutTaskState, PutTaskStatus, PutTaskStop, PutTimestamp, ShmStatus,
+    },
+    zenoh_data::{ZCongestionControl, ZLocality, ZPriority, ZReliability, ZSampleKind},
 };
 use eframe::{
     egui::{
-        CentralPanel, CollapsingHeader, Color32, ComboBox, Context, Grid, Layout, RichText,
-        ScrollArea, SidePanel, TextEdit, TextStyle, Ui, Widget,
+        CentralPanel, Checkbox, CollapsingHeader, Color32, ComboBox, Context, DragValue, Grid,
+        Layout, RichText, ScrollArea, SidePanel, TextEdit, TextStyle, Ui, Widget,
     },
     emath::Align,
 };
@@ -15,20 +19,138 @@ use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, VecDeque},
     str::FromStr,
+    time::Duration,
+};
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
+use uhlc::NTP64;
+use zenoh::{
+    bytes::{Encoding, ZBytes},
+    key_expr::OwnedKeyExpr,
 };
-use strum::IntoEnumIterator;
-use zenoh::key_expr::OwnedKeyExpr;
 
 pub enum Event {
-    Put(Box<PutData>),
+    Put(u64, Box<PutData>),                    // session profile id, put
+    DeclarePublisher(u64, Box<PublisherData>), // session profile id, publisher
+    UndeclarePublisher(u64),                   // put id
+    StartPutTask(u64, Box<PutTaskData>),       // session profile id, task
+    PutTaskCtrl(u64, PutTaskCtrl),             // (put id, ctrl)
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+enum AttachmentType {
+    #[default]
+    Text,
+    Hex,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+enum RepeatStop {
+    #[default]
+    Count,
+    Duration,
+}
+
+// 连续发送的设置
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct PutRepeat {
+    rate: f64, // Hz
+    stop: RepeatStop,
+    count: u64,
+    duration: u64, // ms
+    burst: u64,
+}
+
+impl Default for PutRepeat {
+    fn default() -> Self {
+        PutRepeat {
+            rate: 10.0,
+            stop: RepeatStop::Count,
+            count: 100,
+            duration: 10000,
+            burst: 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+enum MissDetection {
+    Disabled,
+    SequenceNumber,
+    #[default]
+    Heartbeat,
+    SporadicHeartbeat,
+}
+
+// advanced publisher (zenoh-ext) 设置
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct AdvancedPublisherOptions {
+    enabled: bool,
+    cache: bool,
+    cache_size: usize,
+    miss_detection: MissDetection,
+    heartbeat_period: u64, // ms
+    publisher_detection: bool,
+}
+
+impl Default for AdvancedPublisherOptions {
+    fn default() -> Self {
+        AdvancedPublisherOptions {
+            enabled: false,
+            cache: true,
+            cache_size: 10,
+            miss_detection: MissDetection::Heartbeat,
+            heartbeat_period: 1000,
+            publisher_detection: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+enum TimestampMode {
+    #[default]
+    None,
+    Now,
+    Custom,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct ArchivePagePutData {
     name: String,
     key: String,
+    #[serde(default)]
+    kind: ZSampleKind,
     congestion_control: ZCongestionControl,
     priority: ZPriority,
+    #[serde(default)]
+    express: bool,
+    #[serde(default)]
+    reliability: ZReliability,
+    #[serde(default)]
+    destination: ZLocality,
+    #[serde(default)]
+    attachment: String,
+    #[serde(default)]
+    attachment_type: AttachmentType,
+    #[serde(default)]
+    timestamp_mode: TimestampMode,
+    #[serde(default)]
+    timestamp: String,
+    #[serde(default)]
+    repeat: PutRepeat,
+    #[serde(default)]
+    shm: bool,
+    #[serde(default)]
+    session_id: u64, // 配置的 id, 0 表示默认 session
+    #[serde(default)]
+    advanced: AdvancedPublisherOptions,
     archive_payload_edit: ArchivePayloadEdit,
 }
 
@@ -41,10 +163,25 @@ pub struct PagePutData {
     id: u64,
     name: String,
     input_key: String,
+    selected_kind: ZSampleKind,
     selected_congestion_control: ZCongestionControl,
     selected_priority: ZPriority,
+    express: bool,
+    selected_reliability: ZReliability,
+    selected_destination: ZLocality,
+    input_attachment: String,
+    selected_attachment_type: AttachmentType,
+    selected_timestamp_mode: TimestampMode,
+    input_timestamp: String,
+    repeat: PutRepeat,
+    shm: bool,
+    session: u64, // 配置的 id, 0 表示默认 session
+    advanced: AdvancedPublisherOptions,
     payload_edit: PayloadEdit,
     info: Option<Result<String, String>>,
+    put_task: Option<PutTaskStatus>,
+    declared: bool,
+    matching: bool,
 }
 
 impl Default for PagePutData {
@@ -53,10 +190,25 @@ impl Default for PagePutData {
             id: 1,
             name: "demo".to_string(),
             input_key: "demo/example".to_string(),
+            selected_kind: ZSampleKind::Put,
             selected_congestion_control: ZCongestionControl::Block,
             selected_priority: ZPriority::RealTime,
+            express: false,
+            selected_reliability: ZReliability::Reliable,
+            selected_destination: ZLocality::Any,
+            input_attachment: String::new(),
+            selected_attachment_type: AttachmentType::Text,
+            selected_timestamp_mode: TimestampMode::None,
+            input_timestamp: String::new(),
+            repeat: PutRepeat::default(),
+            shm: false,
+            session: 0,
+            advanced: AdvancedPublisherOptions::default(),
             payload_edit: PayloadEdit::default(),
             info: None,
+            put_task: None,
+            declared: false,
+            matching: false,
         }
     }
 }
@@ -67,10 +219,25 @@ impl From<&PagePutData> for PagePutData {
             id: value.id,
             name: value.name.clone(),
             input_key: value.input_key.clone(),
+            selected_kind: value.selected_kind,
             selected_congestion_control: value.selected_congestion_control,
             selected_priority: value.selected_priority,
+            express: value.express,
+            selected_reliability: value.selected_reliability,
+            selected_destination: value.selected_destination,
+            input_attachment: value.input_attachment.clone(),
+            selected_attachment_type: value.selected_attachment_type,
+            selected_timestamp_mode: value.selected_timestamp_mode,
+            input_timestamp: value.input_timestamp.clone(),
+            repeat: value.repeat,
+            shm: value.shm,
+            session: value.session,
+            advanced: value.advanced,
             payload_edit: (&value.payload_edit).into(),
             info: None,
+            put_task: None,
+            declared: false,
+            matching: false,
         }
     }
 }
@@ -80,8 +247,20 @@ impl From<&PagePutData> for ArchivePagePutData {
         ArchivePagePutData {
             name: value.name.clone(),
             key: value.input_key.clone(),
+            kind: value.selected_kind,
             congestion_control: value.selected_congestion_control,
             priority: value.selected_priority,
+            express: value.express,
+            reliability: value.selected_reliability,
+            destination: value.selected_destination,
+            attachment: value.input_attachment.clone(),
+            attachment_type: value.selected_attachment_type,
+            timestamp_mode: value.selected_timestamp_mode,
+            timestamp: value.input_timestamp.clone(),
+            repeat: value.repeat,
+            shm: value.shm,
+            session_id: value.session,
+            advanced: value.advanced,
             archive_payload_edit: (&value.payload_edit).into(),
         }
     }
@@ -95,10 +274,25 @@ impl TryFrom<&ArchivePagePutData> for PagePutData {
             id: 0,
             name: value.name.clone(),
             input_key: value.key.clone(),
+            selected_kind: value.kind,
             selected_congestion_control: value.congestion_control,
             selected_priority: value.priority,
+            express: value.express,
+            selected_reliability: value.reliability,
+            selected_destination: value.destination,
+            input_attachment: value.attachment.clone(),
+            selected_attachment_type: value.attachment_type,
+            selected_timestamp_mode: value.timestamp_mode,
+            input_timestamp: value.timestamp.clone(),
+            repeat: value.repeat,
+            shm: value.shm,
+            session: value.session_id,
+            advanced: value.advanced,
             payload_edit: (&value.archive_payload_edit).try_into()?,
             info: None,
+            put_task: None,
+            declared: false,
+            matching: false,
         })
     }
 }
@@ -111,17 +305,38 @@ impl TryFrom<ArchivePagePutData> for PagePutData {
             id: 0,
             name: value.name,
             input_key: value.key,
+            selected_kind: value.kind,
             selected_congestion_control: value.congestion_control,
             selected_priority: value.priority,
+            express: value.express,
+            selected_reliability: value.reliability,
+            selected_destination: value.destination,
+            input_attachment: value.attachment,
+            selected_attachment_type: value.attachment_type,
+            selected_timestamp_mode: value.timestamp_mode,
+            input_timestamp: value.timestamp,
+            repeat: value.repeat,
+            shm: value.shm,
+            session: value.session_id,
+            advanced: value.advanced,
             payload_edit: value.archive_payload_edit.try_into()?,
             info: None,
+            put_task: None,
+            declared: false,
+            matching: false,
         })
     }
 }
 
 impl PagePutData {
-    fn show(&mut self, ui: &mut Ui, events: &mut VecDeque<Event>) {
-        self.show_name_key(ui, events);
+    fn show(
+        &mut self,
+        ui: &mut Ui,
+        events: &mut VecDeque<Event>,
+        shm_status: Option<&ShmStatus>,
+        sessions: &[SessionEntry],
+    ) {
+        self.show_name_key(ui, events, sessions);
 
         if let Some(info) = &self.info {
             let text = match info {
@@ -135,17 +350,45 @@ impl PagePutData {
             .auto_shrink([false, false])
             .show(ui, |ui| {
                 self.show_options(ui);
-                self.show_payload_edit(ui);
+                self.show_repeat(ui, events);
+                self.show_payload_edit(ui, shm_status);
             });
     }
 
-    fn show_name_key(&mut self, ui: &mut Ui, events: &mut VecDeque<Event>) {
+    fn show_name_key(
+        &mut self,
+        ui: &mut Ui,
+        events: &mut VecDeque<Event>,
+        sessions: &[SessionEntry],
+    ) {
+        // publisher 和连续发送任务属于声明时的 session
+        let session_locked = self.declared
+            || self
+                .put_task
+                .as_ref()
+                .is_some_and(|t| t.state != PutTaskState::Finished);
         let mut input_grid = |ui: &mut Ui| {
             ui.label("name:");
             ui.with_layout(Layout::right_to_left(Align::Min), |ui| {
                 if ui.button("send").clicked() {
                     self.send(events);
                 }
+                if ui
+                    .selectable_label(self.declared, "publisher")
+                    .on_hover_text("declare publisher")
+                    .clicked()
+                {
+                    if self.declared {
+                        events.push_back(Event::UndeclarePublisher(self.id));
+                        self.declared = false;
+                        self.matching = false;
+                    } else {
+                        self.declare_publisher(events);
+                    }
+                }
+                if self.declared {
+                    matching_label(ui, self.matching, "subscribers");
+                }
                 TextEdit::singleline(&mut self.name)
                     .desired_width(3000.0)
                     .font(TextStyle::Monospace)
@@ -158,8 +401,36 @@ impl PagePutData {
                 .desired_rows(1)
                 .desired_width(3000.0)
                 .font(TextStyle::Monospace)
+                .interactive(!self.declared)
                 .ui(ui);
             ui.end_row();
+
+            ui.label("attachment:");
+            ui.horizontal(|ui| {
+                ComboBox::new("attachment type", "")
+                    .selected_text(self.selected_attachment_type.as_ref())
+                    .width(60.0)
+                    .show_ui(ui, |ui| {
+                        for option in AttachmentType::iter() {
+                            ui.selectable_value(
+                                &mut self.selected_attachment_type,
+                                option,
+                                option.as_ref(),
+                            );
+                        }
+                    });
+                TextEdit::singleline(&mut self.input_attachment)
+                    .desired_width(3000.0)
+                    .font(TextStyle::Monospace)
+                    .ui(ui);
+            });
+            ui.end_row();
+
+            ui.label("session:");
+            ui.add_enabled_ui(!session_locked, |ui| {
+                session_selector(ui, "page_put_session", &mut self.session, sessions);
+            });
+            ui.end_row();
         };
 
         Grid::new("input_grid")
@@ -172,28 +443,119 @@ impl PagePutData {
 
     fn show_options(&mut self, ui: &mut Ui) {
         let mut show_grid = |ui: &mut Ui| {
-            ui.label("congestion control:");
-            ComboBox::new("congestion control", "")
-                .selected_text(self.selected_congestion_control.as_ref())
+            ui.label("kind:");
+            ComboBox::new("kind", "")
+                .selected_text(self.selected_kind.as_ref())
                 .show_ui(ui, |ui| {
-                    for option in ZCongestionControl::iter() {
-                        ui.selectable_value(
-                            &mut self.selected_congestion_control,
-                            option,
-                            option.as_ref(),
-                        );
+                    for option in ZSampleKind::iter() {
+                        ui.selectable_value(&mut self.selected_kind, option, option.as_ref());
                     }
                 });
             ui.end_row();
 
+            let declared = self.declared;
+
+            ui.label("congestion control:");
+            ui.add_enabled_ui(!declared, |ui| {
+                ComboBox::new("congestion control", "")
+                    .selected_text(self.selected_congestion_control.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ZCongestionControl::iter() {
+                            ui.selectable_value(
+                                &mut self.selected_congestion_control,
+                                option,
+                                option.as_ref(),
+                            );
+                        }
+                    });
+            });
+            ui.end_row();
+
             ui.label("priority:");
-            ComboBox::new("priority", "")
-                .selected_text(self.selected_priority.as_ref())
-                .show_ui(ui, |ui| {
-                    for option in ZPriority::iter() {
-                        ui.selectable_value(&mut self.selected_priority, option, option.as_ref());
-                    }
-                });
+            ui.add_enabled_ui(!declared, |ui| {
+                ComboBox::new("priority", "")
+                    .selected_text(self.selected_priority.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ZPriority::iter() {
+                            ui.selectable_value(
+                                &mut self.selected_priority,
+                                option,
+                                option.as_ref(),
+                            );
+                        }
+                    });
+            });
+            ui.end_row();
+
+            ui.label("express:");
+            ui.add_enabled(!declared, Checkbox::without_text(&mut self.express));
+            ui.end_row();
+
+            ui.label("reliability:");
+            ui.add_enabled_ui(!declared, |ui| {
+                ComboBox::new("reliability", "")
+                    .selected_text(self.selected_reliability.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ZReliability::iter() {
+                            ui.selectable_value(
+                                &mut self.selected_reliability,
+                                option,
+                                option.as_ref(),
+                            );
+                        }
+                    });
+            });
+            ui.end_row();
+
+            ui.label("allowed destination:");
+            ui.add_enabled_ui(!declared, |ui| {
+                ComboBox::new("allowed destination", "")
+                    .selected_text(self.selected_destination.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ZLocality::iter() {
+                            ui.selectable_value(
+                                &mut self.selected_destination,
+                                option,
+                                option.as_ref(),
+                            );
+                        }
+                    });
+            });
+            ui.end_row();
+
+            ui.label("advanced:")
+                .on_hover_text("declare the publisher as a zenoh-ext advanced publisher");
+            ui.add_enabled(
+                !declared,
+                Checkbox::without_text(&mut self.advanced.enabled),
+            );
+            ui.end_row();
+
+            if self.advanced.enabled {
+                show_advanced_options(ui, &mut self.advanced, declared);
+            }
+
+            ui.label("timestamp:");
+            ui.horizontal(|ui| {
+                ComboBox::new("timestamp", "")
+                    .selected_text(self.selected_timestamp_mode.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in TimestampMode::iter() {
+                            ui.selectable_value(
+                                &mut self.selected_timestamp_mode,
+                                option,
+                                option.as_ref(),
+                            );
+                        }
+                    });
+                if self.selected_timestamp_mode == TimestampMode::Custom {
+                    TextEdit::singleline(&mut self.input_timestamp)
+                        .hint_text("2024-01-01T00:00:00.000000000Z")
+                        .desired_width(300.0)
+                        .font(TextStyle::Monospace)
+                        .ui(ui);
+                }
+            });
             ui.end_row();
         };
 
@@ -209,41 +571,278 @@ impl PagePutData {
             });
     }
 
-    fn show_payload_edit(&mut self, ui: &mut Ui) {
+    fn show_repeat(&mut self, ui: &mut Ui, events: &mut VecDeque<Event>) {
+        let running = matches!(&self.put_task, Some(s) if s.state != PutTaskState::Finished);
+        let mut start = false;
+
+        let mut show_grid = |ui: &mut Ui| {
+            ui.label("rate:");
+            let dv = DragValue::new(&mut self.repeat.rate)
+                .suffix("Hz")
+                .speed(1.0)
+                .range(0.001..=1000000.0);
+            ui.add_enabled(!running, dv);
+            ui.end_row();
+
+            ui.label("burst:");
+            let dv = DragValue::new(&mut self.repeat.burst)
+                .speed(1.0)
+                .range(1..=100000);
+            ui.add_enabled(!running, dv);
+            ui.end_row();
+
+            ui.label("stop after:");
+            ui.add_enabled_ui(!running, |ui| {
+                ui.horizontal(|ui| {
+                    ComboBox::new("repeat stop", "")
+                        .selected_text(self.repeat.stop.as_ref())
+                        .width(80.0)
+                        .show_ui(ui, |ui| {
+                            for option in RepeatStop::iter() {
+                                ui.selectable_value(&mut self.repeat.stop, option, option.as_ref());
+                            }
+                        });
+                    match self.repeat.stop {
+                        RepeatStop::Count => {
+                            let dv = DragValue::new(&mut self.repeat.count)
+                                .speed(1.0)
+                                .range(1..=u64::MAX);
+                            ui.add(dv);
+                        }
+                        RepeatStop::Duration => {
+                            let dv = DragValue::new(&mut self.repeat.duration)
+                                .suffix("ms")
+                                .speed(100.0)
+                                .range(1..=u64::MAX);
+                            ui.add(dv);
+                        }
+                    }
+                });
+            });
+            ui.end_row();
+        };
+
+        CollapsingHeader::new("Repeat")
+            .default_open(false)
+            .show(ui, |ui| {
+                Grid::new("repeat_grid")
+                    .num_columns(2)
+                    .striped(false)
+                    .show(ui, |ui| {
+                        show_grid(ui);
+                    });
+
+                ui.horizontal(|ui| {
+                    match &self.put_task {
+                        Some(s) if s.state == PutTaskState::Running => {
+                            if ui.button("pause").clicked() {
+                                events.push_back(Event::PutTaskCtrl(self.id, PutTaskCtrl::Pause));
+                            }
+                            if ui.button("stop").clicked() {
+                                events.push_back(Event::PutTaskCtrl(self.id, PutTaskCtrl::Stop));
+                            }
+                        }
+                        Some(s) if s.state == PutTaskState::Paused => {
+                            if ui.button("resume").clicked() {
+                                events.push_back(Event::PutTaskCtrl(self.id, PutTaskCtrl::Resume));
+                            }
+                            if ui.button("stop").clicked() {
+                                events.push_back(Event::PutTaskCtrl(self.id, PutTaskCtrl::Stop));
+                            }
+                        }
+                        _ => {
+                            start = ui.button("start").clicked();
+                        }
+                    }
+
+                    if let Some(s) = &self.put_task {
+                        let state = match s.state {
+                            PutTaskState::Running => "running",
+                            PutTaskState::Paused => "paused",
+                            PutTaskState::Finished => "finished",
+                        };
+                        ui.label(
+                            RichText::new(format!(
+                                "{state}, sent: {}, failed: {}, rate: {:.1}Hz",
+                                s.sent, s.failed, s.rate
+                            ))
+                            .monospace(),
+                        );
+                    }
+                });
+
+                if let Some(PutTaskStatus {
+                    last_error: Some(e),
+                    ..
+                }) = &self.put_task
+                {
+                    ui.label(RichText::new(e).color(Color32::RED));
+                }
+            });
+
+        if start {
+            self.start_put_task(events);
+        }
+    }
+
+    fn show_payload_edit(&mut self, ui: &mut Ui, shm_status: Option<&ShmStatus>) {
+        if self.selected_kind == ZSampleKind::Delete {
+            return;
+        }
+
         CollapsingHeader::new("Payload")
             .default_open(true)
             .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.shm, "shm")
+                        .on_hover_text("allocate payload from shm provider");
+                    if self.shm {
+                        shm_status_label(ui, shm_status);
+                    }
+                });
+
                 self.payload_edit.show(ui);
             });
     }
 
-    fn send(&mut self, events: &mut VecDeque<Event>) {
-        let key_str = self.input_key.replace(&[' ', '\t', '\n', '\r'], "");
+    fn declare_publisher(&mut self, events: &mut VecDeque<Event>) {
+        let key_str = self.input_key.replace([' ', '\t', '\n', '\r'], "");
         let key: OwnedKeyExpr = match OwnedKeyExpr::from_str(key_str.as_str()) {
             Ok(o) => o,
             Err(e) => {
-                self.info = Some(Err(format!("{}", e)));
+                self.info = Some(Err(e.to_string()));
                 return;
             }
         };
 
-        let (encoding, payload) = match self.payload_edit.get_zenoh_value() {
+        let d = PublisherData {
+            id: self.id,
+            key,
+            congestion_control: self.selected_congestion_control.into(),
+            priority: self.selected_priority.into(),
+            express: self.express,
+            reliability: self.selected_reliability.into(),
+            destination: self.selected_destination.into(),
+            advanced: self.advanced_pub_data(),
+        };
+        events.push_back(Event::DeclarePublisher(self.session, Box::new(d)));
+        self.info = None;
+        self.declared = true;
+        self.matching = false;
+    }
+
+    fn advanced_pub_data(&self) -> Option<AdvancedPubData> {
+        let advanced = self.advanced;
+        if !advanced.enabled {
+            return None;
+        }
+        let period = Duration::from_millis(advanced.heartbeat_period);
+        let miss_detection = match advanced.miss_detection {
+            MissDetection::Disabled => PubMissDetection::Disabled,
+            MissDetection::SequenceNumber => PubMissDetection::SequenceNumber,
+            MissDetection::Heartbeat => PubMissDetection::Heartbeat(period),
+            MissDetection::SporadicHeartbeat => PubMissDetection::SporadicHeartbeat(period),
+        };
+        Some(AdvancedPubData {
+            cache: advanced.cache.then_some(advanced.cache_size),
+            miss_detection,
+            publisher_detection: advanced.publisher_detection,
+        })
+    }
+
+    fn send(&mut self, events: &mut VecDeque<Event>) {
+        if let Some(put_data) = self.put_data() {
+            events.push_back(Event::Put(self.session, Box::new(put_data)));
+            self.info = None;
+        }
+    }
+
+    fn start_put_task(&mut self, events: &mut VecDeque<Event>) {
+        let put = match self.put_data() {
             None => {
                 return;
             }
             Some(o) => o,
         };
+        let stop = match self.repeat.stop {
+            RepeatStop::Count => PutTaskStop::Count(self.repeat.count),
+            RepeatStop::Duration => {
+                PutTaskStop::Duration(Duration::from_millis(self.repeat.duration))
+            }
+        };
+        let d = PutTaskData {
+            put,
+            rate: self.repeat.rate,
+            stop,
+            burst: self.repeat.burst,
+        };
+        events.push_back(Event::StartPutTask(self.session, Box::new(d)));
+        self.info = None;
+        self.put_task = None;
+    }
+
+    fn put_data(&mut self) -> Option<PutData> {
+        let key_str = self.input_key.replace(&[' ', '\t', '\n', '\r'], "");
+        let key: OwnedKeyExpr = match OwnedKeyExpr::from_str(key_str.as_str()) {
+            Ok(o) => o,
+            Err(e) => {
+                self.info = Some(Err(format!("{}", e)));
+                return None;
+            }
+        };
+
+        let (encoding, payload) = match self.selected_kind {
+            ZSampleKind::Put => match self.payload_edit.get_zenoh_value() {
+                None => {
+                    return None;
+                }
+                Some(o) => o,
+            },
+            ZSampleKind::Delete => (Encoding::default(), ZBytes::new()),
+        };
+
+        let attachment: Option<ZBytes> = if self.input_attachment.is_empty() {
+            None
+        } else {
+            match self.selected_attachment_type {
+                AttachmentType::Text => Some(ZBytes::from(self.input_attachment.as_str())),
+                AttachmentType::Hex => match parse_str_to_vec(self.input_attachment.as_str()) {
+                    Ok(o) => Some(ZBytes::from(o)),
+                    Err(e) => {
+                        self.info = Some(Err(format!("attachment parse error, {e}")));
+                        return None;
+                    }
+                },
+            }
+        };
+
+        let timestamp = match self.selected_timestamp_mode {
+            TimestampMode::None => PutTimestamp::None,
+            TimestampMode::Now => PutTimestamp::Now,
+            TimestampMode::Custom => match NTP64::parse_rfc3339(self.input_timestamp.trim()) {
+                Ok(o) => PutTimestamp::Time(o),
+                Err(e) => {
+                    self.info = Some(Err(format!("timestamp parse error, {}", e.cause)));
+                    return None;
+                }
+            },
+        };
 
-        let put_data = PutData {
+        Some(PutData {
             id: self.id,
             key,
+            kind: self.selected_kind.into(),
             congestion_control: self.selected_congestion_control.into(),
             priority: self.selected_priority.into(),
+            express: self.express,
+            reliability: self.selected_reliability.into(),
+            destination: self.selected_destination.into(),
+            attachment,
+            timestamp,
             encoding,
             payload,
-        };
-        events.push_back(Event::Put(Box::new(put_data)));
-        self.info = None;
+            shm: self.shm,
+        })
     }
 }
 
@@ -253,6 +852,7 @@ pub struct PagePut {
     selected_data_id: u64,
     put_id_count: u64,
     dnd_items: Vec<DndItem>,
+    shm_status: Option<ShmStatus>,
 }
 
 impl Default for PagePut {
@@ -263,6 +863,7 @@ impl Default for PagePut {
             selected_data_id: 1,
             put_id_count: 0,
             dnd_items: Vec::new(),
+            shm_status: None,
         };
         p.add_put_data(PagePutData::default());
         p
@@ -297,7 +898,7 @@ impl PagePut {
         Ok(())
     }
 
-    pub fn show(&mut self, ctx: &Context) {
+    pub fn show(&mut self, ctx: &Context, sessions: &[SessionEntry]) {
         SidePanel::left("page_put_panel_left")
             .resizable(true)
             .show(ctx, |ui| {
@@ -312,7 +913,7 @@ impl PagePut {
                 Some(o) => o,
             };
 
-            data.show(ui, &mut self.events);
+            data.show(ui, &mut self.events, self.shm_status.as_ref(), sessions);
         });
     }
 
@@ -351,8 +952,19 @@ impl PagePut {
                     |ui, item, handle, _state| {
                         if let Some(d) = self.data_map.get(&item.key_id) {
                             handle.ui(ui, |ui| {
-                                let text = RichText::new(d.name.as_str());
-                                ui.selectable_value(&mut self.selected_data_id, item.key_id, text);
+                                ui.horizontal(|ui| {
+                                    let text = if d.declared {
+                                        matching_label(ui, d.matching, "subscribers");
+                                        RichText::new(d.name.as_str()).underline().strong()
+                                    } else {
+                                        RichText::new(d.name.as_str())
+                                    };
+                                    ui.selectable_value(
+                                        &mut self.selected_data_id,
+                                        item.key_id,
+                                        text,
+                                    );
+                                });
                             });
                         }
                     },
@@ -373,7 +985,15 @@ impl PagePut {
             return;
         }
 
-        let _ = self.data_map.remove(&put_id);
+        if let Some(d) = self.data_map.remove(&put_id) {
+            if matches!(&d.put_task, Some(s) if s.state != PutTaskState::Finished) {
+                self.events
+                    .push_back(Event::PutTaskCtrl(put_id, PutTaskCtrl::Stop));
+            }
+            if d.declared {
+                self.events.push_back(Event::UndeclarePublisher(put_id));
+            }
+        }
         let mut del_index = None;
         for (i, di) in self.dnd_items.iter().enumerate() {
             if di.key_id == put_id {
@@ -387,17 +1007,157 @@ impl PagePut {
     }
 
     fn clean_all_put_data(&mut self) {
+        for (id, d) in &self.data_map {
+            if matches!(&d.put_task, Some(s) if s.state != PutTaskState::Finished) {
+                self.events
+                    .push_back(Event::PutTaskCtrl(*id, PutTaskCtrl::Stop));
+            }
+            if d.declared {
+                self.events.push_back(Event::UndeclarePublisher(*id));
+            }
+        }
         self.data_map.clear();
         self.dnd_items.clear();
         self.selected_data_id = 0;
     }
 
-    pub fn processing_put_res(&mut self, r: Box<(u64, bool, String)>) {
-        let (id, b, s) = *r;
+    pub fn processing_put_res(&mut self, r: (u64, bool, String)) {
+        let (id, b, s) = r;
         if let Some(pd) = self.data_map.get_mut(&id) {
             pd.info = if b { Some(Ok(s)) } else { Some(Err(s)) }
         }
     }
+
+    pub fn processing_declare_publisher_res(&mut self, id: u64, r: Result<(), String>) {
+        if let Some(pd) = self.data_map.get_mut(&id) {
+            match r {
+                Ok(_) => {
+                    pd.declared = true;
+                }
+                Err(e) => {
+                    pd.info = Some(Err(e));
+                    pd.declared = false;
+                    pd.matching = false;
+                }
+            }
+        }
+    }
+
+    pub fn processing_undeclare_publisher_res(&mut self, id: u64) {
+        if let Some(pd) = self.data_map.get_mut(&id) {
+            pd.declared = false;
+            pd.matching = false;
+        }
+    }
+
+    pub fn processing_session_terminated(&mut self, id: u64) {
+        if let Some(pd) = self.data_map.get_mut(&id) {
+            pd.declared = false;
+            pd.matching = false;
+            if let Some(task) = &mut pd.put_task {
+                task.state = PutTaskState::Finished;
+                task.rate = 0.0;
+            }
+        }
+    }
+
+    pub fn processing_shm_status(&mut self, status: ShmStatus) {
+        self.shm_status = Some(status);
+    }
+
+    pub fn processing_put_task_status(&mut self, id: u64, status: PutTaskStatus) {
+        if let Some(pd) = self.data_map.get_mut(&id) {
+            pd.put_task = Some(status);
+        }
+    }
+
+    pub fn processing_publisher_matching_status(&mut self, id: u64, matching: bool) {
+        if let Some(pd) = self.data_map.get_mut(&id) {
+            pd.matching = matching;
+        }
+    }
+}
+
+pub(crate) fn shm_status_label(ui: &mut Ui, shm_status: Option<&ShmStatus>) {
+    let Some(status) = shm_status else {
+        return;
+    };
+    ui.label(format!(
+        "pool: {}KiB, available: {}KiB",
+        status.pool_size / 1024,
+        status.available / 1024
+    ));
+    if let Some(e) = &status.error {
+        ui.label(RichText::new(e).color(Color32::RED));
+    }
+}
+
+// 缓存但不检测丢失时, advanced publisher 使用 timestamp 排序, 需要在配置中开启 timestamping
+fn show_advanced_options(ui: &mut Ui, advanced: &mut AdvancedPublisherOptions, declared: bool) {
+    ui.label("cache:")
+        .on_hover_text("keep the last samples for advanced subscribers history and recovery");
+    ui.add_enabled_ui(!declared, |ui| {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut advanced.cache, "");
+            ui.add_enabled(
+                advanced.cache,
+                DragValue::new(&mut advanced.cache_size)
+                    .range(1..=1_000_000)
+                    .prefix("max samples: "),
+            );
+        });
+    });
+    ui.end_row();
+
+    ui.label("miss detection:");
+    ui.add_enabled_ui(!declared, |ui| {
+        ui.horizontal(|ui| {
+            ComboBox::new("miss detection", "")
+                .selected_text(advanced.miss_detection.as_ref())
+                .show_ui(ui, |ui| {
+                    for option in MissDetection::iter() {
+                        ui.selectable_value(&mut advanced.miss_detection, option, option.as_ref());
+                    }
+                });
+            if matches!(
+                advanced.miss_detection,
+                MissDetection::Heartbeat | MissDetection::SporadicHeartbeat
+            ) {
+                ui.add(
+                    DragValue::new(&mut advanced.heartbeat_period)
+                        .range(10..=600_000)
+                        .prefix("period: ")
+                        .suffix(" ms"),
+                );
+            }
+            if advanced.cache && advanced.miss_detection == MissDetection::Disabled {
+                ui.label(
+                    RichText::new("requires \"timestamping\" in the config")
+                        .color(Color32::from_rgb(220, 140, 0)),
+                );
+            }
+        });
+    });
+    ui.end_row();
+
+    ui.label("publisher detection:")
+        .on_hover_text("declare a liveliness token so late joiner subscribers can find it");
+    ui.add_enabled(
+        !declared,
+        Checkbox::without_text(&mut advanced.publisher_detection),
+    );
+    ui.end_row();
+}
+
+// matched 为匹配的对象, 如 subscribers, queryables
+pub(crate) fn matching_label(ui: &mut Ui, matching: bool, matched: &str) {
+    let (color, hover) = if matching {
+        (Color32::GREEN, format!("has matching {matched}"))
+    } else {
+        (Color32::GRAY, format!("no matching {matched}"))
+    };
+    ui.label(RichText::new("●").color(color))
+        .on_hover_text(hover);
 }
 
 #[derive(Hash)]
@@ -0,0 +1,625 @@
+use eframe::egui::{
+    Align, CentralPanel, Checkbox, CollapsingHeader, Color32, ComboBox, Context, Grid, Id, Layout,
+    RichText, ScrollArea, SidePanel, TextEdit, TextStyle, Ui, Widget, Window,
+};
+use egui_dnd::dnd;
+use egui_extras::{Column, TableBody, TableBuilder, TableRow};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    str::FromStr,
+    time::SystemTime,
+};
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
+use zenoh::key_expr::OwnedKeyExpr;
+
+use crate::{
+    payload_editor::{ArchivePayloadEdit, PayloadEdit},
+    query_viewer::QueryViewer,
+    task_zenoh::{QueryRecord, QueryReply, QueryableData},
+    zenoh_data::{system_time_to_string, zenoh_value_abstract, ZLocality},
+};
+
+const QUERY_BUFFER_SIZE: usize = 100;
+
+pub enum Event {
+    AddQueryable(Box<QueryableData>),
+    DelQueryable(u64), // id
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+enum ReplyMode {
+    Reply,
+    ReplyErr,
+    Ignore,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ArchivePageQueryableData {
+    name: String,
+    key_expr: String,
+    complete: bool,
+    origin: ZLocality,
+    reply_mode: ReplyMode,
+    archive_payload_edit: ArchivePayloadEdit,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct ArchivePageQueryable {
+    queryables: Vec<ArchivePageQueryableData>,
+}
+
+pub struct PageQueryableData {
+    id: u64,
+    declared: bool,
+    name: String,
+    input_key: String,
+    complete: bool,
+    selected_origin: ZLocality,
+    selected_reply_mode: ReplyMode,
+    payload_edit: PayloadEdit,
+    err_str: Option<String>,
+    queries: VecDeque<(QueryRecord, SystemTime)>,
+}
+
+impl Default for PageQueryableData {
+    fn default() -> Self {
+        PageQueryableData {
+            id: 1,
+            declared: false,
+            name: "demo".to_string(),
+            input_key: "demo/example".to_string(),
+            complete: false,
+            selected_origin: ZLocality::Any,
+            selected_reply_mode: ReplyMode::Reply,
+            payload_edit: PayloadEdit::default(),
+            err_str: None,
+            queries: VecDeque::new(),
+        }
+    }
+}
+
+impl From<&PageQueryableData> for PageQueryableData {
+    fn from(value: &PageQueryableData) -> Self {
+        PageQueryableData {
+            id: 0,
+            declared: false,
+            name: value.name.clone(),
+            input_key: value.input_key.clone(),
+            complete: value.complete,
+            selected_origin: value.selected_origin,
+            selected_reply_mode: value.selected_reply_mode,
+            payload_edit: (&value.payload_edit).into(),
+            err_str: None,
+            queries: VecDeque::new(),
+        }
+    }
+}
+
+impl From<&PageQueryableData> for ArchivePageQueryableData {
+    fn from(value: &PageQueryableData) -> Self {
+        ArchivePageQueryableData {
+            name: value.name.clone(),
+            key_expr: value.input_key.clone(),
+            complete: value.complete,
+            origin: value.selected_origin,
+            reply_mode: value.selected_reply_mode,
+            archive_payload_edit: (&value.payload_edit).into(),
+        }
+    }
+}
+
+impl TryFrom<ArchivePageQueryableData> for PageQueryableData {
+    type Error = String;
+
+    fn try_from(value: ArchivePageQueryableData) -> Result<Self, Self::Error> {
+        Ok(PageQueryableData {
+            id: 0,
+            declared: false,
+            name: value.name,
+            input_key: value.key_expr,
+            complete: value.complete,
+            selected_origin: value.origin,
+            selected_reply_mode: value.reply_mode,
+            payload_edit: value.archive_payload_edit.try_into()?,
+            err_str: None,
+            queries: VecDeque::new(),
+        })
+    }
+}
+
+impl PageQueryableData {
+    fn show(
+        &mut self,
+        ui: &mut Ui,
+        events: &mut VecDeque<Event>,
+        show_window: &mut bool,
+        query_window: &mut QueryViewer,
+    ) {
+        self.show_name_key(ui, events);
+
+        if let Some(e) = &self.err_str {
+            ui.label(RichText::new(e).color(Color32::RED));
+        }
+
+        ScrollArea::horizontal()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                self.show_options(ui);
+                self.show_reply_edit(ui);
+                ui.separator();
+                self.show_query_table(ui, show_window, query_window);
+            });
+    }
+
+    fn show_name_key(&mut self, ui: &mut Ui, events: &mut VecDeque<Event>) {
+        let mut input_grid = |ui: &mut Ui| {
+            ui.label("name:");
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui.selectable_label(self.declared, "declare").clicked() {
+                    if self.declared {
+                        events.push_back(Event::DelQueryable(self.id));
+                        self.declared = false;
+                    } else {
+                        self.declare(events);
+                    }
+                }
+
+                TextEdit::singleline(&mut self.name)
+                    .desired_width(3000.0)
+                    .font(TextStyle::Monospace)
+                    .interactive(!self.declared)
+                    .ui(ui);
+            });
+            ui.end_row();
+
+            ui.label("key expr:");
+            TextEdit::multiline(&mut self.input_key)
+                .desired_rows(1)
+                .desired_width(3000.0)
+                .font(TextStyle::Monospace)
+                .interactive(!self.declared)
+                .ui(ui);
+            ui.end_row();
+        };
+
+        Grid::new("input_grid")
+            .num_columns(2)
+            .striped(false)
+            .show(ui, |ui| {
+                input_grid(ui);
+            });
+    }
+
+    fn show_options(&mut self, ui: &mut Ui) {
+        let mut show_grid = |ui: &mut Ui| {
+            ui.label("complete:");
+            ui.add_enabled(!self.declared, Checkbox::without_text(&mut self.complete));
+            ui.end_row();
+
+            ui.label("allowed origin:");
+            ui.add_enabled_ui(!self.declared, |ui| {
+                ComboBox::new("queryable_origin", "")
+                    .selected_text(self.selected_origin.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ZLocality::iter() {
+                            ui.selectable_value(&mut self.selected_origin, option, option.as_ref());
+                        }
+                    });
+            });
+            ui.end_row();
+
+            ui.label("reply:");
+            ui.add_enabled_ui(!self.declared, |ui| {
+                ComboBox::new("queryable_reply_mode", "")
+                    .selected_text(self.selected_reply_mode.as_ref())
+                    .show_ui(ui, |ui| {
+                        for option in ReplyMode::iter() {
+                            ui.selectable_value(
+                                &mut self.selected_reply_mode,
+                                option,
+                                option.as_ref(),
+                            );
+                        }
+                    });
+            });
+            ui.end_row();
+        };
+
+        CollapsingHeader::new("Options")
+            .default_open(true)
+            .show(ui, |ui| {
+                Grid::new("options_grid")
+                    .num_columns(2)
+                    .striped(false)
+                    .show(ui, |ui| {
+                        show_grid(ui);
+                    });
+            });
+    }
+
+    fn show_reply_edit(&mut self, ui: &mut Ui) {
+        if self.selected_reply_mode == ReplyMode::Ignore {
+            return;
+        }
+
+        CollapsingHeader::new("Reply payload")
+            .default_open(true)
+            .show(ui, |ui| {
+                ui.add_enabled_ui(!self.declared, |ui| {
+                    self.payload_edit.show(ui);
+                });
+            });
+    }
+
+    fn show_query_table(
+        &mut self,
+        ui: &mut Ui,
+        show_window: &mut bool,
+        query_window: &mut QueryViewer,
+    ) {
+        ui.horizontal(|ui| {
+            ui.label(format!("queries count: {}", self.queries.len()));
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui.button("clean").on_hover_text("clean queries").clicked() {
+                    self.queries.clear();
+                }
+            });
+        });
+
+        if self.queries.is_empty() {
+            return;
+        }
+
+        let table_header = |mut table_row: TableRow| {
+            table_row.col(|ui| {
+                ui.label("time");
+            });
+            table_row.col(|ui| {
+                ui.label("key");
+            });
+            table_row.col(|ui| {
+                ui.label("parameters");
+            });
+            table_row.col(|ui| {
+                ui.label("attachment");
+            });
+            table_row.col(|ui| {
+                ui.label("payload");
+            });
+            table_row.col(|ui| {
+                ui.label("reply");
+            });
+        };
+
+        let table_body = |mut body: TableBody| {
+            for (record, receipt_time) in self.queries.iter().rev() {
+                body.row(20.0, |mut row| {
+                    row.col(|ui| {
+                        let text = system_time_to_string(*receipt_time);
+                        ui.label(RichText::new(text).size(12.0));
+                    });
+                    row.col(|ui| {
+                        ui.label(record.key_expr.as_str());
+                    });
+                    row.col(|ui| {
+                        ui.label(record.parameters.as_str());
+                    });
+                    row.col(|ui| {
+                        let text = match &record.attachment {
+                            None => "-".to_string(),
+                            Some(a) => String::from_utf8(a.to_bytes().to_vec())
+                                .unwrap_or("...".to_string()),
+                        };
+                        ui.label(text);
+                    });
+                    row.col(|ui| {
+                        let rich_text = match &record.value {
+                            None => RichText::new("-"),
+                            Some((encoding, payload)) => {
+                                match zenoh_value_abstract(encoding, payload) {
+                                    Ok(o) => RichText::new(o),
+                                    Err(e) => RichText::new(e).color(Color32::RED),
+                                }
+                            }
+                        };
+                        if ui.button(rich_text).clicked() {
+                            *query_window = QueryViewer::new_from_query_record(record);
+                            *show_window = true;
+                        }
+                    });
+                    row.col(|ui| {
+                        let text = match &record.reply_result {
+                            Ok(_) => RichText::new("ok"),
+                            Err(e) => RichText::new(e).color(Color32::RED),
+                        };
+                        ui.label(text);
+                    });
+                });
+            }
+        };
+
+        let table = TableBuilder::new(ui)
+            .striped(true)
+            .cell_layout(Layout::left_to_right(Align::Center))
+            .column(Column::auto())
+            .column(Column::initial(100.0).resizable(true).clip(true))
+            .column(Column::initial(100.0).resizable(true).clip(true))
+            .column(Column::initial(60.0).resizable(true).clip(true))
+            .column(Column::auto())
+            .column(Column::remainder())
+            .resizable(true);
+
+        table.header(20.0, table_header).body(table_body);
+    }
+
+    fn declare(&mut self, events: &mut VecDeque<Event>) {
+        let key_str = self.input_key.replace([' ', '\t', '\n', '\r'], "");
+        let key_expr: OwnedKeyExpr = match OwnedKeyExpr::from_str(key_str.as_str()) {
+            Ok(o) => o,
+            Err(e) => {
+                self.err_str = Some(e.to_string());
+                return;
+            }
+        };
+
+        let reply = match self.selected_reply_mode {
+            ReplyMode::Reply | ReplyMode::ReplyErr => {
+                let (encoding, payload) = match self.payload_edit.get_zenoh_value() {
+                    None => {
+                        self.err_str = Some("invalid reply payload".to_string());
+                        return;
+                    }
+                    Some(o) => o,
+                };
+                if self.selected_reply_mode == ReplyMode::Reply {
+                    QueryReply::Ok(encoding, payload)
+                } else {
+                    QueryReply::Err(encoding, payload)
+                }
+            }
+            ReplyMode::Ignore => QueryReply::Ignore,
+        };
+
+        let d = QueryableData {
+            id: self.id,
+            key_expr,
+            complete: self.complete,
+            origin: self.selected_origin.into(),
+            reply,
+        };
+        events.push_back(Event::AddQueryable(Box::new(d)));
+        self.err_str = None;
+        self.declared = true;
+    }
+}
+
+pub struct PageQueryable {
+    pub events: VecDeque<Event>,
+    pub data_map: BTreeMap<u64, PageQueryableData>,
+    selected_data_id: u64,
+    queryable_id_count: u64,
+    show_query_viewer_window: bool,
+    query_viewer_window: QueryViewer,
+    dnd_items: Vec<DndItem>,
+}
+
+impl Default for PageQueryable {
+    fn default() -> Self {
+        let mut p = PageQueryable {
+            events: VecDeque::new(),
+            data_map: BTreeMap::new(),
+            selected_data_id: 1,
+            queryable_id_count: 0,
+            show_query_viewer_window: false,
+            query_viewer_window: QueryViewer::default(),
+            dnd_items: Vec::new(),
+        };
+        p.add_queryable_data(PageQueryableData::default());
+        p
+    }
+}
+
+impl From<&PageQueryable> for ArchivePageQueryable {
+    fn from(value: &PageQueryable) -> Self {
+        ArchivePageQueryable {
+            queryables: value
+                .dnd_items
+                .iter()
+                .filter_map(|k| value.data_map.get(&k.key_id))
+                .map(|d| d.into())
+                .collect(),
+        }
+    }
+}
+
+impl PageQueryable {
+    pub fn load(&mut self, archive: ArchivePageQueryable) -> Result<(), String> {
+        let mut data = Vec::with_capacity(archive.queryables.len());
+        for d in archive.queryables {
+            let page_queryable_data = PageQueryableData::try_from(d)?;
+            data.push(page_queryable_data);
+        }
+
+        self.clean_all_queryable_data();
+
+        for d in data {
+            self.add_queryable_data(d);
+        }
+        Ok(())
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        SidePanel::left("page_queryable_panel_left")
+            .resizable(true)
+            .show(ctx, |ui| {
+                self.show_queryables_name(ui);
+            });
+
+        CentralPanel::default().show(ctx, |ui| {
+            let data = match self.data_map.get_mut(&self.selected_data_id) {
+                None => {
+                    return;
+                }
+                Some(o) => o,
+            };
+
+            data.show(
+                ui,
+                &mut self.events,
+                &mut self.show_query_viewer_window,
+                &mut self.query_viewer_window,
+            );
+        });
+
+        let window = Window::new("Query info")
+            .id(Id::new("view query window"))
+            .collapsible(false)
+            .scroll([true, true])
+            .open(&mut self.show_query_viewer_window)
+            .resizable(true)
+            .default_width(200.0)
+            .min_width(200.0);
+
+        window.show(ctx, |ui| {
+            self.query_viewer_window.show(ui);
+        });
+    }
+
+    fn show_queryables_name(&mut self, ui: &mut Ui) {
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            if ui
+                .button(RichText::new(" + ").code())
+                .on_hover_text("copy add")
+                .clicked()
+            {
+                if let Some(d) = self.data_map.get(&self.selected_data_id) {
+                    self.add_queryable_data(PageQueryableData::from(d));
+                } else {
+                    self.add_queryable_data(PageQueryableData::default());
+                }
+            };
+
+            if ui
+                .button(RichText::new(" - ").code())
+                .on_hover_text("del")
+                .clicked()
+            {
+                self.del_queryable_data(self.selected_data_id);
+            };
+        });
+
+        ui.add_space(10.0);
+
+        ScrollArea::both()
+            .max_width(200.0)
+            .auto_shrink([true, false])
+            .show(ui, |ui| {
+                dnd(ui, "page_queryable_list").show_vec(
+                    self.dnd_items.as_mut_slice(),
+                    |ui, item, handle, _state| {
+                        if let Some(d) = self.data_map.get(&item.key_id) {
+                            let text = if d.declared {
+                                RichText::new(d.name.as_str()).underline().strong()
+                            } else {
+                                RichText::new(d.name.as_str())
+                            };
+                            handle.ui(ui, |ui| {
+                                ui.selectable_value(&mut self.selected_data_id, item.key_id, text);
+                            });
+                        }
+                    },
+                )
+            });
+    }
+
+    fn add_queryable_data(&mut self, mut data: PageQueryableData) {
+        self.queryable_id_count += 1;
+        data.id = self.queryable_id_count;
+        self.data_map.insert(self.queryable_id_count, data);
+        self.selected_data_id = self.queryable_id_count;
+        self.dnd_items.push(DndItem::new(self.queryable_id_count));
+    }
+
+    fn del_queryable_data(&mut self, queryable_id: u64) {
+        if self.data_map.len() < 2 {
+            return;
+        }
+
+        if let Some(d) = self.data_map.remove(&queryable_id) {
+            if d.declared {
+                self.events.push_back(Event::DelQueryable(queryable_id));
+            }
+        }
+        let mut del_index = None;
+        for (i, di) in self.dnd_items.iter().enumerate() {
+            if di.key_id == queryable_id {
+                del_index = Some(i);
+                break;
+            }
+        }
+        if let Some(i) = del_index {
+            self.dnd_items.remove(i);
+        }
+    }
+
+    fn clean_all_queryable_data(&mut self) {
+        for (id, d) in &self.data_map {
+            if d.declared {
+                self.events.push_back(Event::DelQueryable(*id));
+            }
+        }
+        self.data_map.clear();
+        self.dnd_items.clear();
+        self.selected_data_id = 0;
+    }
+
+    pub fn processing_add_queryable_res(&mut self, id: u64, r: Result<(), String>) {
+        if let Some(d) = self.data_map.get_mut(&id) {
+            match r {
+                Ok(_) => {
+                    d.err_str = None;
+                    d.declared = true;
+                }
+                Err(e) => {
+                    d.err_str = Some(e);
+                    d.declared = false;
+                }
+            }
+        }
+    }
+
+    pub fn processing_del_queryable_res(&mut self, id: u64) {
+        if let Some(d) = self.data_map.get_mut(&id) {
+            d.declared = false;
+        }
+    }
+
+    pub fn processing_queryable_cb(
+        &mut self,
+        id: u64,
+        record: QueryRecord,
+        receipt_time: SystemTime,
+    ) {
+        if let Some(d) = self.data_map.get_mut(&id) {
+            if d.queries.len() >= QUERY_BUFFER_SIZE {
+                let _ = d.queries.pop_front();
+            }
+            d.queries.push_back((record, receipt_time));
+        }
+    }
+}
+
+#[derive(Hash)]
+struct DndItem {
+    key_id: u64,
+}
+
+impl DndItem {
+    fn new(k: u64) -> Self {
+        DndItem { key_id: k }
+    }
+}
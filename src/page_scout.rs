@@ -0,0 +1,269 @@
+use eframe::egui::{
+    Align, Button, CentralPanel, Checkbox, CollapsingHeader, Color32, Context, DragValue, Grid,
+    Layout, RichText, ScrollArea, TextEdit, TextStyle, Ui, Widget,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::Duration,
+};
+use zenoh::config::WhatAmIMatcher;
+
+use crate::task_zenoh::{HelloInfo, ScoutData};
+
+pub enum Event {
+    Start(Box<ScoutData>),
+    Stop,
+    NewConfig(Box<(String, String)>), // (name, locator)
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ArchivePageScout {
+    router: bool,
+    peer: bool,
+    client: bool,
+    multicast_address: String,
+    multicast_interface: String,
+    timeout: u64,
+}
+
+impl Default for ArchivePageScout {
+    fn default() -> Self {
+        (&PageScout::default()).into()
+    }
+}
+
+pub struct PageScout {
+    pub events: VecDeque<Event>,
+    router: bool,
+    peer: bool,
+    client: bool,
+    multicast_address: String,
+    multicast_interface: String,
+    timeout: u64, // ms
+    running: bool,
+    err_str: Option<String>,
+    hellos: BTreeMap<String, HelloInfo>, // <zid, hello>
+    selected: Option<(String, String)>,  // (zid, locator)
+}
+
+impl Default for PageScout {
+    fn default() -> Self {
+        PageScout {
+            events: VecDeque::new(),
+            router: true,
+            peer: true,
+            client: false,
+            multicast_address: "224.0.0.224:7446".to_string(),
+            multicast_interface: "auto".to_string(),
+            timeout: 3000,
+            running: false,
+            err_str: None,
+            hellos: BTreeMap::new(),
+            selected: None,
+        }
+    }
+}
+
+impl From<&PageScout> for ArchivePageScout {
+    fn from(value: &PageScout) -> Self {
+        ArchivePageScout {
+            router: value.router,
+            peer: value.peer,
+            client: value.client,
+            multicast_address: value.multicast_address.clone(),
+            multicast_interface: value.multicast_interface.clone(),
+            timeout: value.timeout,
+        }
+    }
+}
+
+impl PageScout {
+    pub fn load(&mut self, archive: ArchivePageScout) -> Result<(), String> {
+        self.router = archive.router;
+        self.peer = archive.peer;
+        self.client = archive.client;
+        self.multicast_address = archive.multicast_address;
+        self.multicast_interface = archive.multicast_interface;
+        self.timeout = archive.timeout;
+        Ok(())
+    }
+
+    pub fn show(&mut self, ctx: &Context) {
+        CentralPanel::default().show(ctx, |ui| {
+            ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    self.show_options(ui);
+                    ui.separator();
+                    self.show_hellos(ui);
+                });
+        });
+    }
+
+    fn show_options(&mut self, ui: &mut Ui) {
+        let running = self.running;
+
+        ui.horizontal(|ui| {
+            ui.label("what:");
+            ui.add_enabled(!running, Checkbox::new(&mut self.router, "router"));
+            ui.add_enabled(!running, Checkbox::new(&mut self.peer, "peer"));
+            ui.add_enabled(!running, Checkbox::new(&mut self.client, "client"));
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if running {
+                    if ui.button("stop").clicked() {
+                        self.events.push_back(Event::Stop);
+                    }
+                    ui.spinner();
+                } else if ui.button("start").clicked() {
+                    self.start();
+                }
+            });
+        });
+
+        if let Some(e) = &self.err_str {
+            ui.label(RichText::new(e).color(Color32::RED));
+        }
+
+        CollapsingHeader::new("Options")
+            .default_open(true)
+            .show(ui, |ui| {
+                Grid::new("page_scout_options_grid")
+                    .num_columns(2)
+                    .striped(false)
+                    .show(ui, |ui| {
+                        ui.label("multicast address:");
+                        TextEdit::singleline(&mut self.multicast_address)
+                            .desired_width(3000.0)
+                            .font(TextStyle::Monospace)
+                            .interactive(!running)
+                            .ui(ui);
+                        ui.end_row();
+
+                        ui.label("multicast interface:");
+                        TextEdit::singleline(&mut self.multicast_interface)
+                            .desired_width(3000.0)
+                            .font(TextStyle::Monospace)
+                            .interactive(!running)
+                            .ui(ui);
+                        ui.end_row();
+
+                        ui.label("timeout:");
+                        let dv = DragValue::new(&mut self.timeout)
+                            .suffix("ms")
+                            .speed(10.0)
+                            .range(100..=60000);
+                        ui.add_enabled(!running, dv);
+                        ui.end_row();
+                    });
+            });
+    }
+
+    fn show_hellos(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label(format!("found: {}", self.hellos.len()));
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                let enabled = self.selected.is_some();
+                if ui
+                    .add_enabled(enabled, Button::new("new session config"))
+                    .on_hover_text("add a client config connecting to the selected locator")
+                    .clicked()
+                {
+                    if let Some((zid, locator)) = &self.selected {
+                        let name = format!("scout {}", zid.get(..8).unwrap_or(zid.as_str()));
+                        self.events
+                            .push_back(Event::NewConfig(Box::new((name, locator.clone()))));
+                    }
+                }
+            });
+        });
+
+        ui.add_space(4.0);
+
+        Grid::new("page_scout_hello_grid")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("zid");
+                ui.label("whatami");
+                ui.label("locators");
+                ui.end_row();
+
+                for hello in self.hellos.values() {
+                    ui.label(RichText::new(hello.zid.as_str()).monospace());
+                    ui.label(hello.whatami.as_str());
+                    ui.vertical(|ui| {
+                        for locator in &hello.locators {
+                            let value = (hello.zid.clone(), locator.clone());
+                            let checked = self.selected.as_ref() == Some(&value);
+                            if ui
+                                .selectable_label(checked, RichText::new(locator).monospace())
+                                .clicked()
+                            {
+                                self.selected = Some(value);
+                            }
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn start(&mut self) {
+        let mut what = WhatAmIMatcher::empty();
+        if self.router {
+            what = what.router();
+        }
+        if self.peer {
+            what = what.peer();
+        }
+        if self.client {
+            what = what.client();
+        }
+        if what.is_empty() {
+            self.err_str = Some("nothing to scout".to_string());
+            return;
+        }
+
+        let data = ScoutData {
+            what,
+            multicast_address: self.multicast_address.trim().to_string(),
+            multicast_interface: self.multicast_interface.trim().to_string(),
+            duration: Duration::from_millis(self.timeout),
+        };
+
+        self.err_str = None;
+        self.hellos.clear();
+        self.selected = None;
+        self.running = true;
+        self.events.push_back(Event::Start(Box::new(data)));
+    }
+
+    pub fn processing_scout_hello(&mut self, hello: HelloInfo) {
+        match self.hellos.get_mut(&hello.zid) {
+            Some(h) => {
+                for locator in hello.locators {
+                    if !h.locators.contains(&locator) {
+                        h.locators.push(locator);
+                    }
+                }
+            }
+            None => {
+                let _ = self.hellos.insert(hello.zid.clone(), hello);
+            }
+        }
+    }
+
+    pub fn processing_scout_finish(&mut self, r: Result<(), String>) {
+        self.running = false;
+        if let Err(e) = r {
+            self.err_str = Some(e);
+        }
+    }
+
+    pub fn set_err(&mut self, e: String) {
+        self.err_str = Some(e);
+    }
+}
@@ -1,6 +1,6 @@
 use eframe::egui::{
-    Align, CentralPanel, Color32, Context, Grid, Layout, RichText, ScrollArea, SidePanel, TextEdit,
-    TextStyle, Ui, Widget,
+    Align, Button, CentralPanel, CollapsingHeader, Color32, ComboBox, Context, Grid, Layout,
+    RichText, ScrollArea, SidePanel, TextEdit, TextStyle, Ui, Widget,
 };
 use egui_dnd::dnd;
 use egui_file::{DialogType, FileDialog};
@@ -9,26 +9,211 @@ use log::{info, warn};
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::{
-    collections::{BTreeMap, VecDeque},
+    collections::{BTreeMap, BTreeSet, VecDeque},
     fs,
     path::PathBuf,
     str::FromStr,
+    time::{Duration, Instant},
 };
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
+use zenoh::config::WhatAmI;
+
+use crate::{
+    config_diff::ConfigDiff,
+    config_form::ConfigForm,
+    security_wizard::SecurityWizard,
+    task_zenoh::{
+        build_config, ConfigOverrides, EmbeddedNodeData, SessionConfig, SessionHealth,
+        SessionInfoData,
+    },
+};
+
+const REOPEN_BACKOFF_MIN: Duration = Duration::from_secs(1);
+const REOPEN_BACKOFF_MAX: Duration = Duration::from_secs(30);
+// 连续多少次报告 disconnected 后才重新打开, 避免刚打开时还没连上就被关闭
+const DISCONNECTED_REPORTS_BEFORE_REOPEN: u32 = 2;
+const PASSWORD_REQUIRED: &str = "usrpwd password required, enter it in Security and apply";
 
 pub enum Event {
-    Connect(Box<(u64, PathBuf)>),
-    Disconnect,
+    Connect(Box<(u64, SessionConfig, ConfigOverrides)>),
+    Disconnect(u64), // config file id
+    StartEmbeddedNode(Box<EmbeddedNodeData>),
+    StopEmbeddedNode,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct ArchiveConfigFileData {
+    #[serde(default)]
+    id: u64, // 其他页面的 item 通过 id 绑定 session, 旧的工作区文件中没有
     name: String,
     path: String,
+    #[serde(default)]
+    auto_reopen: bool,
+    #[serde(default)]
+    form: Option<ConfigForm>,
+    #[serde(default)]
+    inline: bool,
+    #[serde(default)]
+    source: String, // 嵌入到工作区文件中的配置
+    #[serde(default)]
+    overrides: Vec<ConfigOverride>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct ConfigOverride {
+    key: String,
+    value: String, // json5
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
 pub struct ArchivePageSession {
     config_files: Vec<ArchiveConfigFileData>,
+    #[serde(default)]
+    embedded_node: ArchiveEmbeddedNode,
+    #[serde(default)]
+    config_file_id_count: u64, // 删除的配置的 id 不再使用, item 不会绑定到新的配置
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+enum EmbeddedMode {
+    #[default]
+    Router,
+    Peer,
+}
+
+impl From<EmbeddedMode> for WhatAmI {
+    fn from(value: EmbeddedMode) -> Self {
+        match value {
+            EmbeddedMode::Router => WhatAmI::Router,
+            EmbeddedMode::Peer => WhatAmI::Peer,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ArchiveEmbeddedNode {
+    mode: EmbeddedMode,
+    listen: String,
+}
+
+impl Default for ArchiveEmbeddedNode {
+    fn default() -> Self {
+        (&EmbeddedNode::default()).into()
+    }
+}
+
+// 在 hammer 内运行的 router/peer
+struct EmbeddedNode {
+    mode: EmbeddedMode,
+    listen: String,
+    running: bool,
+    err_str: Option<String>,
+    info: Option<SessionInfoData>,
+}
+
+impl Default for EmbeddedNode {
+    fn default() -> Self {
+        EmbeddedNode {
+            mode: EmbeddedMode::Router,
+            listen: "tcp/127.0.0.1:7447".to_string(),
+            running: false,
+            err_str: None,
+            info: None,
+        }
+    }
+}
+
+impl From<&EmbeddedNode> for ArchiveEmbeddedNode {
+    fn from(value: &EmbeddedNode) -> Self {
+        ArchiveEmbeddedNode {
+            mode: value.mode,
+            listen: value.listen.clone(),
+        }
+    }
+}
+
+impl EmbeddedNode {
+    // 返回 true 表示需要添加一个连接到这个节点的配置
+    fn show(&mut self, ui: &mut Ui, events: &mut VecDeque<Event>) -> bool {
+        let running = self.running;
+        let mut new_config = false;
+
+        Grid::new("page_session_embedded_node_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("mode");
+                ui.horizontal(|ui| {
+                    for mode in EmbeddedMode::iter() {
+                        ui.add_enabled_ui(!running, |ui| {
+                            ui.selectable_value(&mut self.mode, mode, mode.as_ref());
+                        });
+                    }
+
+                    ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                        if running {
+                            if ui.button("stop").clicked() {
+                                events.push_back(Event::StopEmbeddedNode);
+                            }
+                            if self.info.is_none() {
+                                ui.spinner();
+                            }
+                            if ui
+                                .button("new session config")
+                                .on_hover_text("add a client config connecting to this node")
+                                .clicked()
+                            {
+                                new_config = true;
+                            }
+                        } else if ui.button("start").clicked() {
+                            self.err_str = None;
+                            self.running = true;
+                            let data = EmbeddedNodeData {
+                                mode: self.mode.into(),
+                                listen: self.listen.trim().to_string(),
+                            };
+                            events.push_back(Event::StartEmbeddedNode(Box::new(data)));
+                        }
+                    });
+                });
+                ui.end_row();
+
+                ui.label("listen");
+                TextEdit::singleline(&mut self.listen)
+                    .desired_width(3000.0)
+                    .font(TextStyle::Monospace)
+                    .interactive(!running)
+                    .ui(ui);
+                ui.end_row();
+            });
+
+        if let Some(s) = &self.err_str {
+            ui.label(RichText::new(s).color(Color32::RED));
+        }
+
+        if let Some(info) = &self.info {
+            let clients = info
+                .transports
+                .iter()
+                .flatten()
+                .filter(|t| t.whatami == "client")
+                .count();
+            ui.add_space(4.0);
+            ui.label(format!("connected clients: {clients}"));
+            show_session_info(ui, info);
+        }
+
+        new_config
+    }
+
+    // 监听所有地址时使用本地回环地址连接
+    fn connect_endpoint(&self) -> String {
+        self.listen
+            .trim()
+            .replace("0.0.0.0", "127.0.0.1")
+            .replace("[::]", "[::1]")
+    }
 }
 
 struct ConfigFileData {
@@ -37,10 +222,26 @@ struct ConfigFileData {
     path: Option<PathBuf>,
     path_str: String,
     err_str: Option<String>,
+    terminated: Option<String>, // session 关闭的原因
+    auto_reopen: bool,
+    reopen: Option<Reopen>,
+    disconnected_reports: u32,
     selected_page: FilePage,
     source: String,
+    source_err: Option<String>, // 编辑后校验的错误
+    dirty: bool,                // source 修改后还没有保存
+    save_dialog: Option<FileDialog>,
+    form: Option<ConfigForm>, // 使用表单生成的配置, source 只读
+    inline: bool,             // 打开 session 时使用 source, 不读取文件
+    overrides: Vec<ConfigOverride>,
+    overrides_err: Option<String>, // source 有效时应用 overrides 的错误
+    security: SecurityWizard,
     format: String,
     serde_json_value: serde_json::Value,
+    effective: bool, // format 和 tree 显示合并默认值和 overrides 后的配置
+    effective_err: Option<String>,
+    effective_format: String,
+    effective_value: serde_json::Value,
 }
 
 impl From<&ConfigFileData> for ArchiveConfigFileData {
@@ -49,10 +250,22 @@ impl From<&ConfigFileData> for ArchiveConfigFileData {
             None => String::new(),
             Some(o) => o.to_string_lossy().to_string(),
         };
+        // 表单配置保存表单, 没有文件路径的配置总是嵌入
+        let source = if value.form.is_none() && (value.inline || value.path.is_none()) {
+            value.source.clone()
+        } else {
+            String::new()
+        };
 
         ArchiveConfigFileData {
+            id: value.id,
             name: value.name.clone(),
             path,
+            auto_reopen: value.auto_reopen,
+            form: value.form.clone(),
+            inline: value.inline,
+            source,
+            overrides: value.overrides.clone(),
         }
     }
 }
@@ -64,13 +277,29 @@ impl TryFrom<&ArchiveConfigFileData> for ConfigFileData {
         Ok(ConfigFileData {
             id: 0,
             name: value.name.clone(),
-            path: PathBuf::from_str(value.path.as_str()).ok(),
+            path: archive_path(value.path.as_str()),
             path_str: String::new(),
             err_str: None,
+            terminated: None,
+            auto_reopen: value.auto_reopen,
+            reopen: None,
+            disconnected_reports: 0,
             selected_page: FilePage::Source,
-            source: String::new(),
+            source: value.source.clone(),
+            source_err: None,
+            overrides_err: None,
+            dirty: false,
+            save_dialog: None,
+            form: value.form.clone(),
+            inline: value.inline,
+            overrides: value.overrides.clone(),
             format: String::new(),
             serde_json_value: serde_json::Value::Null,
+            effective: false,
+            effective_err: None,
+            effective_format: String::new(),
+            effective_value: serde_json::Value::Null,
+            security: SecurityWizard::default(),
         })
     }
 }
@@ -80,49 +309,162 @@ impl TryFrom<ArchiveConfigFileData> for ConfigFileData {
 
     fn try_from(value: ArchiveConfigFileData) -> Result<Self, Self::Error> {
         Ok(ConfigFileData {
-            id: 0,
+            id: value.id,
             name: value.name,
-            path: PathBuf::from_str(value.path.as_str()).ok(),
+            path: archive_path(value.path.as_str()),
             path_str: String::new(),
             err_str: None,
+            terminated: None,
+            auto_reopen: value.auto_reopen,
+            reopen: None,
+            disconnected_reports: 0,
             selected_page: FilePage::Source,
-            source: String::new(),
+            source: value.source,
+            source_err: None,
+            overrides_err: None,
+            dirty: false,
+            save_dialog: None,
+            form: value.form,
+            inline: value.inline,
+            overrides: value.overrides,
             format: String::new(),
             serde_json_value: serde_json::Value::Null,
+            effective: false,
+            effective_err: None,
+            effective_format: String::new(),
+            effective_value: serde_json::Value::Null,
+            security: SecurityWizard::default(),
         })
     }
 }
 
 impl ConfigFileData {
-    fn new(name: String, path: PathBuf) -> Self {
+    fn new(name: String, path: Option<PathBuf>) -> Self {
         ConfigFileData {
             id: 0,
             name,
-            path: Some(path),
+            path,
             path_str: String::new(),
             err_str: None,
+            terminated: None,
+            auto_reopen: false,
+            reopen: None,
+            disconnected_reports: 0,
             selected_page: FilePage::Source,
             source: String::new(),
+            source_err: None,
+            overrides_err: None,
+            dirty: false,
+            save_dialog: None,
+            form: None,
+            inline: false,
+            overrides: Vec::new(),
             format: String::new(),
             serde_json_value: serde_json::Value::Null,
+            effective: false,
+            effective_err: None,
+            effective_format: String::new(),
+            effective_value: serde_json::Value::Null,
+            security: SecurityWizard::default(),
+        }
+    }
+
+    // 没有文件路径, 嵌入到工作区或者有未保存的修改时使用 source 中的配置
+    fn session_config(&self) -> Result<SessionConfig, String> {
+        match &self.path {
+            Some(p) if !self.dirty && !self.inline => Ok(SessionConfig::File(p.clone())),
+            _ if !self.source.is_empty() => Ok(SessionConfig::Json5(self.source.clone())),
+            Some(p) => Ok(SessionConfig::File(p.clone())),
+            None => Err("no file path".to_string()),
+        }
+    }
+
+    fn override_pairs(&self) -> ConfigOverrides {
+        self.overrides
+            .iter()
+            .filter(|o| !o.key.trim().is_empty())
+            .map(|o| (o.key.trim().to_string(), o.value.clone()))
+            .chain(self.security.password_override())
+            .collect()
+    }
+
+    fn password_required(&self) -> bool {
+        let keys: Vec<&str> = self.overrides.iter().map(|o| o.key.trim()).collect();
+        self.security.password_required(&keys)
+    }
+
+    fn connect_event(&self) -> Result<Event, String> {
+        if self.password_required() {
+            return Err(PASSWORD_REQUIRED.to_string());
         }
+        let config = self.session_config()?;
+        Ok(Event::Connect(Box::new((
+            self.id,
+            config,
+            self.override_pairs(),
+        ))))
     }
 
     fn show(
         &mut self,
         ui: &mut Ui,
-        connected_config_file_id: Option<u64>,
+        connected: bool,
+        session_info: Option<&SessionInfoData>,
         events: &mut VecDeque<Event>,
-    ) {
-        self.show_name_path(ui, connected_config_file_id, events);
+    ) -> Option<ConfigForm> {
+        let mut export_form = None;
+        self.show_name_path(ui, connected, events);
 
         if let Some(s) = &self.err_str {
             ui.label(RichText::new(s).color(Color32::RED));
         }
 
+        if !connected {
+            if let Some(s) = &self.terminated {
+                let text = format!("session terminated, {s}");
+                ui.label(RichText::new(text).color(Color32::from_rgb(220, 140, 0)));
+            }
+        }
+
+        if let Some(reopen) = &self.reopen {
+            let text = match reopen.next {
+                Some(t) => {
+                    let secs = t.saturating_duration_since(Instant::now()).as_secs_f32();
+                    format!("reopening in {:.1}s, attempt {}", secs, reopen.attempt + 1)
+                }
+                None => format!("reopening, attempt {}", reopen.attempt + 1),
+            };
+            ui.label(RichText::new(text).color(Color32::from_rgb(220, 140, 0)));
+        }
+
+        self.show_overrides(ui, connected);
+
+        if !connected && self.password_required() {
+            ui.label(RichText::new(PASSWORD_REQUIRED).color(Color32::from_rgb(220, 140, 0)));
+        }
+
+        if let Some(pairs) = self.security.show(ui, !connected) {
+            self.set_overrides(pairs);
+        }
+
+        if connected {
+            if let Some(info) = session_info {
+                ui.add_space(4.0);
+                show_session_info(ui, info);
+            }
+        }
+
         ui.add_space(10.0);
 
         ui.horizontal(|ui| {
+            if self.form.is_some()
+                && ui
+                    .selectable_label(self.selected_page == FilePage::Form, "form")
+                    .clicked()
+            {
+                self.selected_page = FilePage::Form;
+            }
+
             if ui
                 .selectable_label(self.selected_page == FilePage::Source, "source")
                 .clicked()
@@ -143,68 +485,262 @@ impl ConfigFileData {
             {
                 self.selected_page = FilePage::Tree;
             }
+
+            ui.separator();
+
+            if ui
+                .checkbox(&mut self.effective, "effective")
+                .on_hover_text(
+                    "show the config zenoh uses: defaults, this config and the overrides",
+                )
+                .changed()
+                && self.effective
+            {
+                self.update_effective();
+            }
+
+            ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
+                if ui.button("save as ..").clicked() {
+                    let mut dialog = FileDialog::save_file(self.path.clone())
+                        .show_new_folder(true)
+                        .show_rename(true);
+                    dialog.open();
+                    self.save_dialog = Some(dialog);
+                }
+
+                if ui.button("save").clicked() {
+                    if let Some(p) = self.path.clone() {
+                        self.save_to_file(p);
+                    } else {
+                        let mut dialog = FileDialog::save_file(None)
+                            .show_new_folder(true)
+                            .show_rename(true);
+                        dialog.open();
+                        self.save_dialog = Some(dialog);
+                    }
+                }
+
+                if self.form.is_none()
+                    && ui
+                        .add_enabled(self.source_err.is_none(), Button::new("to form"))
+                        .on_hover_text("create a form config from this config")
+                        .clicked()
+                {
+                    export_form = Some(ConfigForm::from_value(&self.serde_json_value));
+                }
+
+                if self.dirty {
+                    ui.label(RichText::new("modified").color(Color32::from_rgb(220, 140, 0)));
+                }
+            });
         });
 
+        if let Some(e) = &self.source_err {
+            ui.label(RichText::new(e).color(Color32::RED));
+        }
+
+        let show_effective =
+            self.effective && matches!(self.selected_page, FilePage::Format | FilePage::Tree);
+        if show_effective {
+            if let Some(e) = &self.effective_err {
+                ui.label(RichText::new(e).color(Color32::RED));
+            }
+        }
+
         ui.add_space(4.0);
 
         ScrollArea::both()
             .auto_shrink([false, true])
             .show(ui, |ui| match self.selected_page {
+                FilePage::Form => {
+                    if let Some(form) = &mut self.form {
+                        if form.show(ui, !connected) {
+                            self.dirty = self.path.is_some();
+                            self.update_from_form();
+                        }
+                    }
+                }
                 FilePage::Source => {
-                    TextEdit::multiline(&mut self.source)
+                    let response = TextEdit::multiline(&mut self.source)
                         .desired_width(f32::INFINITY)
                         .code_editor()
-                        .interactive(false)
+                        .interactive(!connected && self.form.is_none())
                         .ui(ui);
+                    if response.changed() {
+                        self.dirty = true;
+                        self.validate();
+                    }
                 }
                 FilePage::Format => {
-                    TextEdit::multiline(&mut self.format)
+                    let text = if show_effective {
+                        &mut self.effective_format
+                    } else {
+                        &mut self.format
+                    };
+                    TextEdit::multiline(text)
                         .desired_width(f32::INFINITY)
                         .code_editor()
                         .interactive(false)
                         .ui(ui);
                 }
                 FilePage::Tree => {
-                    JsonTree::new("page_session_json_tree", &self.serde_json_value).show(ui);
+                    let value = if show_effective {
+                        &self.effective_value
+                    } else {
+                        &self.serde_json_value
+                    };
+                    JsonTree::new("page_session_json_tree", value).show(ui);
                 }
             });
+
+        if let Some(dialog) = &mut self.save_dialog {
+            if dialog.show(ui.ctx()).selected() {
+                if let DialogType::SaveFile = dialog.dialog_type() {
+                    if let Some(p) = dialog.path() {
+                        let p = p.to_path_buf();
+                        self.save_to_file(p);
+                    }
+                }
+            }
+        }
+
+        export_form
     }
 
-    fn show_name_path(
-        &mut self,
-        ui: &mut Ui,
-        connected_config_file_id: Option<u64>,
-        events: &mut VecDeque<Event>,
-    ) {
+    fn update_from_form(&mut self) {
+        if let Some(form) = &self.form {
+            self.source = form.to_json5();
+            self.validate();
+        }
+    }
+
+    fn show_overrides(&mut self, ui: &mut Ui, connected: bool) {
+        let mut changed = false;
+        CollapsingHeader::new(format!("Overrides ({})", self.overrides.len()))
+            .id_salt("page_session_overrides")
+            .default_open(false)
+            .show(ui, |ui| {
+                let mut del_index = None;
+                Grid::new("page_session_overrides_grid")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (i, o) in self.overrides.iter_mut().enumerate() {
+                            changed |= TextEdit::singleline(&mut o.key)
+                                .id_salt(("page_session_override_key", i))
+                                .desired_width(200.0)
+                                .font(TextStyle::Monospace)
+                                .hint_text("connect/endpoints")
+                                .interactive(!connected)
+                                .ui(ui)
+                                .changed();
+                            changed |= TextEdit::singleline(&mut o.value)
+                                .id_salt(("page_session_override_value", i))
+                                .desired_width(3000.0)
+                                .font(TextStyle::Monospace)
+                                .hint_text("[\"tcp/127.0.0.1:7447\"]")
+                                .interactive(!connected)
+                                .ui(ui)
+                                .changed();
+                            if ui
+                                .add_enabled(!connected, Button::new(RichText::new(" - ").code()))
+                                .clicked()
+                            {
+                                del_index = Some(i);
+                            }
+                            ui.end_row();
+                        }
+                    });
+                if let Some(i) = del_index {
+                    self.overrides.remove(i);
+                    changed = true;
+                }
+                if ui
+                    .add_enabled(!connected, Button::new(RichText::new(" + ").code()))
+                    .on_hover_text("the value is applied with Config::insert_json5 before opening")
+                    .clicked()
+                {
+                    self.overrides.push(ConfigOverride::default());
+                }
+            });
+        if let Some(e) = &self.overrides_err {
+            ui.label(RichText::new(e).color(Color32::RED));
+        }
+        if changed {
+            if !self.source.is_empty() {
+                self.validate();
+            } else if self.effective {
+                self.update_effective();
+            }
+        }
+    }
+
+    // 替换已有的同名 override, 没有时添加到最后
+    fn set_overrides(&mut self, pairs: ConfigOverrides) {
+        for (key, value) in pairs {
+            match self.overrides.iter_mut().find(|o| o.key.trim() == key) {
+                Some(o) => o.value = value,
+                None => self.overrides.push(ConfigOverride { key, value }),
+            }
+        }
+        if !self.source.is_empty() {
+            self.validate();
+        } else if self.effective {
+            self.update_effective();
+        }
+    }
+
+    fn show_name_path(&mut self, ui: &mut Ui, connected: bool, events: &mut VecDeque<Event>) {
         Grid::new("page_session_config_file")
             .num_columns(2)
             .show(ui, |ui| {
                 ui.label("name");
                 ui.with_layout(Layout::right_to_left(Align::Center), |ui| {
-                    let mut flag_self_connected = false;
+                    let flag_self_connected = connected;
 
-                    if let Some(id) = connected_config_file_id {
-                        flag_self_connected = id == self.id;
-                        ui.add_enabled_ui(flag_self_connected, |ui| {
-                            if ui
-                                .selectable_label(flag_self_connected, "close session")
-                                .clicked()
-                            {
-                                events.push_back(Event::Disconnect);
-                            }
-                        });
+                    if connected {
+                        if ui.selectable_label(true, "close session").clicked() {
+                            self.reopen = None;
+                            events.push_back(Event::Disconnect(self.id));
+                        }
                     } else {
                         if ui.selectable_label(false, "open session").clicked() {
                             self.err_str = None;
-                            if let Some(path_buf) = &self.path {
-                                let event = Event::Connect(Box::new((self.id, path_buf.clone())));
-                                events.push_back(event);
-                            } else {
-                                self.err_str = Some("no file path".to_string());
+                            self.terminated = None;
+                            self.reopen = None;
+                            match self.connect_event() {
+                                Ok(event) => {
+                                    events.push_back(event);
+                                }
+                                Err(e) => {
+                                    self.err_str = Some(e);
+                                }
                             }
                         }
                     }
 
+                    if ui
+                        .checkbox(&mut self.auto_reopen, "auto reopen")
+                        .on_hover_text("reopen the session with backoff when it is disconnected")
+                        .changed()
+                        && !self.auto_reopen
+                    {
+                        self.reopen = None;
+                    }
+
+                    if self.form.is_none()
+                        && ui
+                            .checkbox(&mut self.inline, "embed")
+                            .on_hover_text("store the config in the workspace file and open the session from it")
+                            .changed()
+                        && self.inline
+                        && self.source.is_empty()
+                    {
+                        if let Some(path_buf) = &self.path {
+                            self.load_from_file(path_buf.clone());
+                        }
+                    }
+
                     ui.add_enabled_ui(!flag_self_connected, |ui| {
                         if ui.button("load").clicked() {
                             self.err_str = None;
@@ -225,8 +761,14 @@ impl ConfigFileData {
                 ui.end_row();
 
                 ui.label("path");
-                if let Some(s) = &self.path {
-                    self.path_str = s.to_string_lossy().to_string();
+                match &self.path {
+                    Some(s) => {
+                        self.path_str = s.to_string_lossy().to_string();
+                    }
+                    None if !self.source.is_empty() => {
+                        self.path_str = "(not saved)".to_string();
+                    }
+                    None => {}
                 }
                 TextEdit::multiline(&mut self.path_str)
                     .desired_rows(1)
@@ -237,6 +779,24 @@ impl ConfigFileData {
             });
     }
 
+    fn schedule_reopen(&mut self) {
+        let attempt = self
+            .reopen
+            .as_ref()
+            .map_or(0, |r| r.attempt.saturating_add(1));
+        let backoff = reopen_backoff(attempt);
+        info!(
+            "session \"{}\" reopen in {:?}, attempt {}",
+            self.name,
+            backoff,
+            attempt + 1
+        );
+        self.reopen = Some(Reopen {
+            attempt,
+            next: Some(Instant::now() + backoff),
+        });
+    }
+
     fn load_from_file(&mut self, p: PathBuf) {
         info!("loading config file \"{}\"", p.display());
         self.source.clear();
@@ -253,43 +813,112 @@ impl ConfigFileData {
             }
         };
 
-        let serde_json_value = match json5::from_str::<serde_json::Value>(source.as_str()) {
+        // 格式错误时也显示内容, 方便在编辑器中修改
+        self.source = source;
+        self.dirty = false;
+        self.validate();
+        if let Some(e) = &self.source_err {
+            warn!("invalid config file, {e}");
+        }
+
+        info!("load config file ok \"{}\"", p.display());
+    }
+
+    fn save_to_file(&mut self, p: PathBuf) {
+        match fs::write(p.as_path(), self.source.as_bytes()) {
+            Ok(_) => {
+                info!("save config file ok \"{}\"", p.display());
+                self.err_str = None;
+                self.dirty = false;
+                self.path = Some(p);
+            }
+            Err(e) => {
+                warn!("failed to save config file, {e}");
+                self.err_str = Some(format!("failed to save config file, {e}"));
+            }
+        }
+    }
+
+    fn validate(&mut self) {
+        self.validate_source();
+        if self.effective {
+            self.update_effective();
+        }
+    }
+
+    // 与打开 session 时使用相同的配置
+    fn effective_config(&self) -> Result<serde_json::Value, String> {
+        let config = build_config(self.session_config()?, &self.override_pairs())?;
+        serde_json::from_str(config.to_string().as_str()).map_err(|e| e.to_string())
+    }
+
+    fn update_effective(&mut self) {
+        match self.effective_config() {
+            Ok(value) => {
+                self.effective_err = None;
+                self.effective_format = serde_json::to_string_pretty(&value).unwrap_or_default();
+                self.effective_value = value;
+            }
+            Err(e) => {
+                self.effective_err = Some(e);
+                self.effective_format.clear();
+                self.effective_value = serde_json::Value::Null;
+            }
+        }
+    }
+
+    // 先检查 json5 语法, 再检查是否是有效的 zenoh 配置, 最后检查 overrides, 错误分开显示
+    fn validate_source(&mut self) {
+        self.source_err = None;
+        self.overrides_err = None;
+        let serde_json_value = match json5::from_str::<serde_json::Value>(self.source.as_str()) {
             Ok(o) => o,
             Err(e) => {
-                warn!("failed to load config file, {}", e);
-                self.err_str = Some("failed to load config file".to_string());
+                self.source_err = Some(json5_error_string(&e));
                 return;
             }
         };
-
-        self.source = source;
+        let config = |s: &String| SessionConfig::Json5(s.clone());
+        if let Err(e) = build_config(config(&self.source), &[]) {
+            self.source_err = Some(e);
+        } else if !self.overrides.is_empty() {
+            if let Err(e) = build_config(config(&self.source), &self.override_pairs()) {
+                self.overrides_err = Some(e);
+            }
+        }
         self.format = serde_json::to_string_pretty(&serde_json_value).unwrap_or_default();
         self.serde_json_value = serde_json_value;
-
-        info!("load config file ok \"{}\"", p.display());
     }
 }
 
 pub struct PageSession {
     pub events: VecDeque<Event>,
-    connected_config_file_id: Option<u64>,
+    connected_config_file_ids: BTreeSet<u64>,
     selected_config_file_id: u64,
     config_file_id_count: u64,
     config_files: BTreeMap<u64, ConfigFileData>,
     dnd_items: Vec<DndItem>,
     file_dialog: Option<FileDialog>,
+    session_info: BTreeMap<u64, SessionInfoData>, // <config file id, info>
+    embedded_node: EmbeddedNode,
+    central_view: CentralView,
+    diff: ConfigDiff,
 }
 
 impl Default for PageSession {
     fn default() -> Self {
         PageSession {
             events: VecDeque::new(),
-            connected_config_file_id: None,
+            connected_config_file_ids: BTreeSet::new(),
             selected_config_file_id: 0,
             config_file_id_count: 0,
             config_files: BTreeMap::new(),
             dnd_items: Vec::new(),
             file_dialog: None,
+            session_info: BTreeMap::new(),
+            embedded_node: EmbeddedNode::default(),
+            central_view: CentralView::ConfigFile,
+            diff: ConfigDiff::default(),
         }
     }
 }
@@ -303,6 +932,8 @@ impl From<&PageSession> for ArchivePageSession {
                 .filter_map(|k| value.config_files.get(&k.id))
                 .map(|d| d.into())
                 .collect(),
+            embedded_node: (&value.embedded_node).into(),
+            config_file_id_count: value.config_file_id_count,
         }
     }
 }
@@ -311,15 +942,25 @@ impl PageSession {
     pub fn load(&mut self, archive: ArchivePageSession) -> Result<(), String> {
         let mut data = Vec::with_capacity(archive.config_files.len());
         for d in archive.config_files {
-            let config_file_data = ConfigFileData::try_from(d)?;
+            let mut config_file_data = ConfigFileData::try_from(d)?;
+            if config_file_data.form.is_some() {
+                config_file_data.selected_page = FilePage::Form;
+                config_file_data.update_from_form();
+            } else if !config_file_data.source.is_empty() {
+                config_file_data.validate();
+            }
             data.push(config_file_data);
         }
 
         self.clean_all_config_file_data();
+        self.config_file_id_count = archive.config_file_id_count;
 
         for d in data {
             self.add_config_file(d);
         }
+
+        self.embedded_node.mode = archive.embedded_node.mode;
+        self.embedded_node.listen = archive.embedded_node.listen;
         Ok(())
     }
 
@@ -330,10 +971,43 @@ impl PageSession {
                 self.show_config_file_list(ui);
             });
 
-        CentralPanel::default().show(ctx, |ui| {
-            if let Some(config_file_data) = self.config_files.get_mut(&self.selected_config_file_id)
-            {
-                config_file_data.show(ui, self.connected_config_file_id, &mut self.events);
+        CentralPanel::default().show(ctx, |ui| match self.central_view {
+            CentralView::EmbeddedNode => {
+                if self.embedded_node.show(ui, &mut self.events) {
+                    let locator = self.embedded_node.connect_endpoint();
+                    if let Err(e) = self.add_locator_config("embedded".to_string(), locator) {
+                        self.embedded_node.err_str = Some(e);
+                    }
+                }
+            }
+            CentralView::Diff => {
+                let profiles: Vec<(u64, String)> = self
+                    .dnd_items
+                    .iter()
+                    .filter_map(|k| self.config_files.get(&k.id))
+                    .map(|d| (d.id, d.name.clone()))
+                    .collect();
+                if self.diff.show(ui, &profiles) {
+                    self.compare_configs();
+                }
+            }
+            CentralView::ConfigFile => {
+                let Some(config_file_data) =
+                    self.config_files.get_mut(&self.selected_config_file_id)
+                else {
+                    return;
+                };
+                let id = self.selected_config_file_id;
+                let export_form = config_file_data.show(
+                    ui,
+                    self.connected_config_file_ids.contains(&id),
+                    self.session_info.get(&id),
+                    &mut self.events,
+                );
+                if let Some(form) = export_form {
+                    let name = format!("{} form", config_file_data.name);
+                    self.add_form_config(name, form);
+                }
             }
         });
 
@@ -359,21 +1033,69 @@ impl PageSession {
                 None => "new file".to_string(),
                 Some(o) => o.to_string_lossy().to_string(),
             };
-            self.add_config_file(ConfigFileData::new(name, p));
+            self.add_config_file(ConfigFileData::new(name, Some(p)));
         }
     }
 
     fn add_config_file(&mut self, mut config_file_data: ConfigFileData) {
-        self.config_file_id_count += 1;
-        let id = self.config_file_id_count;
+        // 从工作区文件加载时保留原来的 id
+        let id = match config_file_data.id {
+            0 => self.config_file_id_count + 1,
+            id if self.config_files.contains_key(&id) => self.config_file_id_count + 1,
+            id => id,
+        };
+        self.config_file_id_count = self.config_file_id_count.max(id);
         self.selected_config_file_id = id;
 
         config_file_data.id = id;
+        self.central_view = CentralView::ConfigFile;
 
         self.config_files.insert(id, config_file_data);
         self.dnd_items.push(DndItem { id });
     }
 
+    // 根据 scout 发现的 locator 生成一个连接它的配置, 没有保存到文件
+    pub fn add_locator_config(&mut self, name: String, locator: String) -> Result<(), String> {
+        let endpoint = serde_json::to_string(&locator).map_err(|e| e.to_string())?;
+        let source = format!(
+            "{{\n  mode: \"client\",\n  connect: {{\n    endpoints: [{endpoint}],\n  }},\n}}\n"
+        );
+        let mut config_file_data = ConfigFileData::new(name, None);
+        config_file_data.source = source;
+        config_file_data.dirty = true;
+        config_file_data.validate();
+        self.add_config_file(config_file_data);
+        Ok(())
+    }
+
+    fn add_form_config(&mut self, name: String, form: ConfigForm) {
+        let mut config_file_data = ConfigFileData::new(name, None);
+        config_file_data.form = Some(form);
+        config_file_data.selected_page = FilePage::Form;
+        config_file_data.update_from_form();
+        self.add_config_file(config_file_data);
+    }
+
+    fn compare_configs(&mut self) {
+        let effective = |id: u64| -> Result<serde_json::Value, String> {
+            let cf = self
+                .config_files
+                .get(&id)
+                .ok_or("select two configs to compare".to_string())?;
+            cf.effective_config()
+                .map_err(|e| format!("\"{}\": {}", cf.name, e))
+        };
+        match (effective(self.diff.left), effective(self.diff.right)) {
+            (Ok(left), Ok(right)) => {
+                self.diff.compare(&left, &right);
+            }
+            (Err(e), _) | (_, Err(e)) => {
+                self.diff.clear();
+                self.diff.err_str = Some(e);
+            }
+        }
+    }
+
     fn del_config_file(&mut self) {
         if self.config_files.len() < 2 {
             return;
@@ -381,10 +1103,8 @@ impl PageSession {
 
         let remove_id = self.selected_config_file_id;
 
-        if let Some(id) = self.connected_config_file_id {
-            if id == remove_id {
-                return;
-            }
+        if self.connected_config_file_ids.contains(&remove_id) {
+            return;
         }
 
         let _ = self.config_files.remove(&remove_id);
@@ -402,30 +1122,151 @@ impl PageSession {
     }
 
     pub fn connected(&self) -> bool {
-        self.connected_config_file_id.is_some()
+        !self.connected_config_file_ids.is_empty()
+    }
+
+    // reason 为 None 表示 session 意外退出
+    pub fn set_terminated(&mut self, id: u64, reason: Option<String>) {
+        self.set_disconnected(id);
+        if let Some(cf) = self.config_files.get_mut(&id) {
+            if reason.is_none() && cf.auto_reopen && cf.reopen.is_none() {
+                cf.schedule_reopen();
+            }
+            cf.terminated = Some(reason.unwrap_or("engine exited unexpectedly".to_string()));
+        }
+    }
+
+    // 是否正在等待自动重新打开
+    pub fn reopening(&self, id: u64) -> bool {
+        self.config_files
+            .get(&id)
+            .is_some_and(|cf| cf.reopen.is_some())
+    }
+
+    // 到时间后重新打开 session
+    pub fn poll_reopen(&mut self) {
+        let now = Instant::now();
+        for cf in self.config_files.values_mut() {
+            if self.connected_config_file_ids.contains(&cf.id) {
+                continue;
+            }
+            let Some(reopen) = &mut cf.reopen else {
+                continue;
+            };
+            if reopen.next.is_none_or(|t| t > now) {
+                continue;
+            }
+            reopen.next = None;
+            match cf.connect_event() {
+                Ok(event) => {
+                    self.events.push_back(event);
+                }
+                Err(e) => {
+                    cf.reopen = None;
+                    cf.err_str = Some(e);
+                }
+            }
+        }
+    }
+
+    pub fn processing_embedded_node_info(&mut self, info: SessionInfoData) {
+        if self.embedded_node.running {
+            self.embedded_node.info = Some(info);
+        }
     }
 
-    pub fn set_connected(&mut self, connected_id: Option<u64>) {
-        self.connected_config_file_id = connected_id;
+    pub fn processing_embedded_node_finish(&mut self, r: Result<(), String>) {
+        self.embedded_node.running = false;
+        self.embedded_node.info = None;
+        if let Err(e) = r {
+            self.embedded_node.err_str = Some(e);
+        }
+    }
+
+    pub fn set_disconnected(&mut self, id: u64) {
+        let _ = self.connected_config_file_ids.remove(&id);
+        let _ = self.session_info.remove(&id);
+    }
+
+    // 按列表顺序返回所有配置的名称和状态, 供其他页面选择 session
+    pub fn session_list(&self) -> Vec<SessionEntry> {
+        self.dnd_items
+            .iter()
+            .filter_map(|k| self.config_files.get(&k.id))
+            .map(|d| SessionEntry {
+                id: d.id,
+                name: d.name.clone(),
+                health: self.connected_config_file_ids.contains(&d.id).then(|| {
+                    self.session_info
+                        .get(&d.id)
+                        .map_or(SessionHealth::default(), |i| i.health)
+                }),
+            })
+            .collect()
+    }
+
+    // profile 为配置的 id, 0 表示列表中第一个已打开的 session
+    pub fn session_id(&self, profile: u64) -> Option<u64> {
+        if profile != 0 {
+            return self
+                .connected_config_file_ids
+                .contains(&profile)
+                .then_some(profile);
+        }
+        self.dnd_items
+            .iter()
+            .map(|k| k.id)
+            .find(|id| self.connected_config_file_ids.contains(id))
+    }
+
+    pub fn session_name(&self, profile: u64) -> Option<&str> {
+        self.config_files.get(&profile).map(|d| d.name.as_str())
     }
 
     pub fn set_connect_result(&mut self, r: Result<u64, (u64, String)>) {
         match r {
             Ok(id) => {
-                self.connected_config_file_id = Some(id);
+                let _ = self.connected_config_file_ids.insert(id);
                 if let Some(cf) = self.config_files.get_mut(&id) {
                     cf.err_str = None;
                 }
             }
             Err((id, s)) => {
-                self.connected_config_file_id = None;
+                self.set_disconnected(id);
                 if let Some(cf) = self.config_files.get_mut(&id) {
                     cf.err_str = Some(s);
+                    if cf.reopen.is_some() {
+                        cf.schedule_reopen();
+                    }
                 }
             }
         }
     }
 
+    pub fn processing_session_info(&mut self, id: u64, info: SessionInfoData) {
+        if !self.connected_config_file_ids.contains(&id) {
+            return;
+        }
+        if let Some(cf) = self.config_files.get_mut(&id) {
+            if info.health == SessionHealth::Disconnected {
+                cf.disconnected_reports += 1;
+            } else {
+                cf.disconnected_reports = 0;
+            }
+            if info.health == SessionHealth::Connected {
+                cf.reopen = None;
+            } else if cf.auto_reopen
+                && cf.disconnected_reports >= DISCONNECTED_REPORTS_BEFORE_REOPEN
+                && cf.reopen.as_ref().is_none_or(|r| r.next.is_none())
+            {
+                cf.disconnected_reports = 0;
+                cf.schedule_reopen();
+                self.events.push_back(Event::Disconnect(id));
+            }
+        }
+        let _ = self.session_info.insert(id, info);
+    }
+
     fn show_config_file_list(&mut self, ui: &mut Ui) {
         ui.add_space(4.0);
 
@@ -445,10 +1286,49 @@ impl PageSession {
             if ui.button(RichText::new(" - ").code()).clicked() {
                 self.del_config_file();
             }
+
+            if ui
+                .button(RichText::new("form").code())
+                .on_hover_text("Add a zenoh session configuration built from a form")
+                .clicked()
+            {
+                self.add_form_config("new form".to_string(), ConfigForm::default());
+            }
         });
 
         ui.add_space(10.0);
 
+        let text = if self.embedded_node.running {
+            RichText::new("embedded node").underline().strong()
+        } else {
+            RichText::new("embedded node")
+        };
+        if ui
+            .selectable_label(self.central_view == CentralView::EmbeddedNode, text)
+            .on_hover_text("Run a router or peer inside this app")
+            .clicked()
+        {
+            self.central_view = CentralView::EmbeddedNode;
+        }
+
+        if ui
+            .selectable_label(self.central_view == CentralView::Diff, "config diff")
+            .on_hover_text("Compare the effective configs of two profiles")
+            .clicked()
+        {
+            self.central_view = CentralView::Diff;
+            // 默认对比列表中的前两个配置
+            if !self.config_files.contains_key(&self.diff.left) {
+                self.diff.left = self.dnd_items.first().map_or(0, |k| k.id);
+            }
+            if !self.config_files.contains_key(&self.diff.right) {
+                self.diff.right = self.dnd_items.get(1).map_or(0, |k| k.id);
+            }
+            self.compare_configs();
+        }
+
+        ui.separator();
+
         ScrollArea::both()
             .max_width(200.0)
             .auto_shrink([true, false])
@@ -457,22 +1337,26 @@ impl PageSession {
                     self.dnd_items.as_mut_slice(),
                     |ui, item, handle, _state| {
                         if let Some(d) = self.config_files.get(&item.id) {
-                            let text = if let Some(id) = self.connected_config_file_id {
-                                if id == item.id {
-                                    RichText::new(d.name.as_str()).underline().strong()
-                                } else {
-                                    RichText::new(d.name.as_str())
-                                }
+                            let name = if d.dirty {
+                                format!("{} *", d.name)
                             } else {
-                                RichText::new(d.name.as_str())
+                                d.name.clone()
+                            };
+                            let text = if self.connected_config_file_ids.contains(&item.id) {
+                                RichText::new(name).underline().strong()
+                            } else if d.password_required() {
+                                RichText::new(name).color(Color32::from_rgb(220, 140, 0))
+                            } else {
+                                RichText::new(name)
                             };
 
                             handle.ui(ui, |ui| {
-                                ui.selectable_value(
-                                    &mut self.selected_config_file_id,
-                                    item.id,
-                                    text,
-                                );
+                                let checked = self.central_view == CentralView::ConfigFile
+                                    && self.selected_config_file_id == item.id;
+                                if ui.selectable_label(checked, text).clicked() {
+                                    self.selected_config_file_id = item.id;
+                                    self.central_view = CentralView::ConfigFile;
+                                }
                             });
                         }
                     },
@@ -483,17 +1367,237 @@ impl PageSession {
     fn clean_all_config_file_data(&mut self) {
         self.selected_config_file_id = 0;
         self.config_files.clear();
+        self.dnd_items.clear();
+    }
+}
+
+fn json5_error_string(e: &json5::Error) -> String {
+    let json5::Error::Message { msg, location } = e;
+    // pest 的错误信息有多行, 只保留最后一行的说明
+    let msg = msg
+        .lines()
+        .last()
+        .unwrap_or_default()
+        .trim()
+        .trim_start_matches("= ");
+    match location {
+        Some(l) => format!("line {}, column {}: {}", l.line, l.column, msg),
+        None => msg.to_string(),
     }
 }
 
+fn archive_path(s: &str) -> Option<PathBuf> {
+    if s.is_empty() {
+        return None;
+    }
+    PathBuf::from_str(s).ok()
+}
+
+// 其他页面选择 session 时使用, health 为 None 表示 session 没有打开
+pub struct SessionEntry {
+    id: u64,
+    name: String,
+    health: Option<SessionHealth>,
+}
+
+// Sub/Get/Put 等页面中选择使用的 session, item 通过配置的 id 绑定, 0 表示默认 session
+pub fn session_selector(
+    ui: &mut Ui,
+    id_salt: impl std::hash::Hash,
+    selected: &mut u64,
+    sessions: &[SessionEntry],
+) {
+    let entry = sessions.iter().find(|s| s.id == *selected);
+    let text = match (*selected, entry) {
+        (0, _) => "default".to_string(),
+        (_, Some(s)) => s.name.clone(),
+        (_, None) => "deleted".to_string(),
+    };
+    ui.horizontal(|ui| {
+        ComboBox::from_id_salt(id_salt)
+            .selected_text(text)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(selected, 0, "default")
+                    .on_hover_text("the first opened session in the session list");
+                for s in sessions {
+                    ui.selectable_value(selected, s.id, s.name.as_str());
+                }
+            });
+
+        // 默认 session 为列表中第一个已打开的 session
+        let health = if *selected == 0 {
+            sessions.iter().find_map(|s| s.health)
+        } else {
+            sessions
+                .iter()
+                .find(|s| s.id == *selected)
+                .and_then(|s| s.health)
+        };
+        match health {
+            Some(health) => {
+                let (text, color) = health_text(health);
+                ui.label(RichText::new(text).color(color))
+                    .on_hover_text(health_hover_text(health));
+            }
+            None => {
+                ui.label(RichText::new("not opened").weak());
+            }
+        }
+    });
+}
+
+fn health_text(health: SessionHealth) -> (&'static str, Color32) {
+    match health {
+        SessionHealth::Connected => ("connected", Color32::DARK_GREEN),
+        SessionHealth::Degraded => ("degraded", Color32::from_rgb(220, 140, 0)),
+        SessionHealth::Disconnected => ("disconnected", Color32::RED),
+    }
+}
+
+fn health_hover_text(health: SessionHealth) -> &'static str {
+    match health {
+        SessionHealth::Connected => "connected to at least one router or peer",
+        SessionHealth::Degraded => {
+            "no router or peer connected, only local communication works until a peer is found"
+        }
+        SessionHealth::Disconnected => {
+            "no router or peer connected, the configured ones are unreachable"
+        }
+    }
+}
+
+// 自动重新打开的等待时间, 从 REOPEN_BACKOFF_MIN 开始每次翻倍, 不超过 REOPEN_BACKOFF_MAX
+fn reopen_backoff(attempt: u32) -> Duration {
+    REOPEN_BACKOFF_MIN
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(REOPEN_BACKOFF_MAX)
+}
+
+fn show_session_info(ui: &mut Ui, info: &SessionInfoData) {
+    let zid_list = |ui: &mut Ui, zids: &Vec<String>| {
+        if zids.is_empty() {
+            ui.label("-");
+        } else {
+            ui.vertical(|ui| {
+                for zid in zids {
+                    ui.label(RichText::new(zid).monospace());
+                }
+            });
+        }
+    };
+
+    CollapsingHeader::new("Session info")
+        .default_open(true)
+        .show(ui, |ui| {
+            Grid::new("page_session_info_grid")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("zid");
+                    ui.label(RichText::new(info.zid.as_str()).monospace());
+                    ui.end_row();
+
+                    ui.label("mode");
+                    ui.label(RichText::new(info.mode.as_str()).monospace());
+                    ui.end_row();
+
+                    ui.label("health");
+                    let (text, color) = health_text(info.health);
+                    ui.label(RichText::new(text).color(color))
+                        .on_hover_text(health_hover_text(info.health));
+                    ui.end_row();
+
+                    ui.label("routers");
+                    zid_list(ui, &info.routers);
+                    ui.end_row();
+
+                    ui.label("peers");
+                    zid_list(ui, &info.peers);
+                    ui.end_row();
+
+                    if info.transports.is_some() {
+                        ui.label("locators");
+                        zid_list(ui, &info.locators);
+                        ui.end_row();
+                    }
+                });
+
+            ui.add_space(4.0);
+
+            let Some(transports) = &info.transports else {
+                ui.label(
+                    RichText::new("transports: enable \"adminspace\" in the config to show links")
+                        .weak(),
+                );
+                return;
+            };
+            if transports.is_empty() {
+                ui.label("transports: -");
+                return;
+            }
+            Grid::new("page_session_transports_grid")
+                .num_columns(4)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(RichText::new("zid").strong());
+                    ui.label(RichText::new("whatami").strong());
+                    ui.label(RichText::new("src").strong());
+                    ui.label(RichText::new("dst").strong());
+                    ui.end_row();
+
+                    for t in transports {
+                        ui.label(RichText::new(t.zid.as_str()).monospace());
+                        ui.label(t.whatami.as_str());
+                        if t.links.is_empty() {
+                            ui.label("-");
+                            ui.label("-");
+                            ui.end_row();
+                        }
+                        for (i, (src, dst)) in t.links.iter().enumerate() {
+                            if i > 0 {
+                                ui.label("");
+                                ui.label("");
+                            }
+                            ui.label(RichText::new(src).monospace());
+                            ui.label(RichText::new(dst).monospace());
+                            ui.end_row();
+                        }
+                    }
+                });
+        });
+}
+
 #[derive(Hash)]
 struct DndItem {
     id: u64,
 }
 
+struct Reopen {
+    attempt: u32,
+    next: Option<Instant>, // None 表示已经发出打开请求, 等待结果
+}
+
+#[derive(Eq, PartialEq, Copy, Clone)]
+enum CentralView {
+    ConfigFile,
+    EmbeddedNode,
+    Diff,
+}
+
 #[derive(Eq, PartialEq, Copy, Clone)]
 enum FilePage {
+    Form,
     Source,
     Format,
     Tree,
 }
+
+#[test]
+fn reopen_backoff_doubling_and_cap() {
+    assert_eq!(reopen_backoff(0), REOPEN_BACKOFF_MIN);
+    assert_eq!(reopen_backoff(1), REOPEN_BACKOFF_MIN * 2);
+    assert_eq!(reopen_backoff(3), REOPEN_BACKOFF_MIN * 8);
+    // 1s * 2^5 = 32s, 超过上限
+    assert_eq!(reopen_backoff(5), REOPEN_BACKOFF_MAX);
+    assert_eq!(reopen_backoff(u32::MAX), REOPEN_BACKOFF_MAX);
+}
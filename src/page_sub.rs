@@ -13,20 +13,91 @@ use std::{
     str::FromStr,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
-use strum::IntoEnumIterator;
+use strum::{AsRefStr, EnumIter, IntoEnumIterator};
 use zenoh::{key_expr::OwnedKeyExpr, sample::Sample};
 
 use crate::{
+    page_session::{session_selector, SessionEntry},
     sample_viewer::SampleViewer,
-    task_zenoh::SubData,
+    task_zenoh::{AdvancedSubData, SampleMiss, SubData, SubDropPolicy, SubHistory, SubRecovery},
     zenoh_data::{zenoh_value_abstract, ZLocality},
 };
 
 pub const VALUE_BUFFER_SIZE_DEFAULT: usize = 10;
 
 pub enum Event {
-    AddSub(Box<SubData>), // id, key expr
-    DelSub(u64),          // id
+    AddSub(u64, Box<SubData>), // session profile id, (id, key expr)
+    DelSub(u64),               // id
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+enum DropPolicy {
+    #[default]
+    DropOldest,
+    KeepLatest,
+    SampleEvery,
+}
+
+// 发送到 gui 之前的缓存队列设置
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct SubQueueOptions {
+    queue_size: usize,
+    batch_period: u64, // ms
+    drop_policy: DropPolicy,
+    sample_every: u64,
+}
+
+impl Default for SubQueueOptions {
+    fn default() -> Self {
+        SubQueueOptions {
+            queue_size: 1000,
+            batch_period: 50,
+            drop_policy: DropPolicy::DropOldest,
+            sample_every: 10,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+enum Recovery {
+    #[default]
+    Disabled,
+    Heartbeat,
+    PeriodicQueries,
+}
+
+// advanced subscriber (zenoh-ext) 设置
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct AdvancedOptions {
+    enabled: bool,
+    history: bool,
+    limit_samples: bool,
+    max_samples: usize,
+    limit_age: bool,
+    max_age: f64, // s
+    detect_late_publishers: bool,
+    recovery: Recovery,
+    query_period: u64, // ms
+}
+
+impl Default for AdvancedOptions {
+    fn default() -> Self {
+        AdvancedOptions {
+            enabled: false,
+            history: true,
+            limit_samples: true,
+            max_samples: 10,
+            limit_age: false,
+            max_age: 60.0,
+            detect_late_publishers: true,
+            recovery: Recovery::Heartbeat,
+            query_period: 1000,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -34,6 +105,12 @@ pub struct ArchivePageSubData {
     name: String,
     key_expr: String,
     origin: ZLocality,
+    #[serde(default)]
+    session_id: u64, // 配置的 id, 0 表示默认 session
+    #[serde(default)]
+    queue: SubQueueOptions,
+    #[serde(default)]
+    advanced: AdvancedOptions,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -99,7 +176,7 @@ impl PageSub {
         Ok(())
     }
 
-    pub fn show(&mut self, ctx: &Context) {
+    pub fn show(&mut self, ctx: &Context, sessions: &[SessionEntry]) {
         SidePanel::left("page_sub_panel_left")
             .resizable(true)
             .show(ctx, |ui| {
@@ -107,7 +184,7 @@ impl PageSub {
             });
 
         CentralPanel::default().show(ctx, |ui| {
-            self.show_name_key(ui);
+            self.show_name_key(ui, sessions);
 
             ui.separator();
 
@@ -223,7 +300,7 @@ impl PageSub {
             });
     }
 
-    fn show_name_key(&mut self, ui: &mut Ui) {
+    fn show_name_key(&mut self, ui: &mut Ui, sessions: &[SessionEntry]) {
         let data_group = match self.sub_data_group.get_mut(&self.selected_sub_id) {
             None => {
                 return;
@@ -241,31 +318,18 @@ impl PageSub {
                         .clicked()
                     {
                         if !data_group.subscribed {
-                            let key_expr_str =
-                                data_group.key_expr.replace(&[' ', '\t', '\n', '\r'], "");
-
-                            if key_expr_str.is_empty() {
-                                let rt = "key expr is empty".to_string();
-                                data_group.err_str = Some(rt);
-                                return;
-                            }
-
-                            let key: OwnedKeyExpr =
-                                match OwnedKeyExpr::from_str(key_expr_str.as_str()) {
-                                    Ok(o) => o,
-                                    Err(e) => {
-                                        data_group.err_str = Some(e.to_string());
-                                        return;
-                                    }
-                                };
-
-                            let sub_data = SubData {
-                                id: self.selected_sub_id,
-                                key_expr: key,
-                                origin: data_group.selected_origin.into(),
+                            let sub_data = match data_group.sub_data(self.selected_sub_id) {
+                                Ok(o) => o,
+                                Err(e) => {
+                                    data_group.err_str = Some(e);
+                                    return;
+                                }
                             };
+                            data_group.dropped = 0;
+                            data_group.misses.clear();
 
-                            self.events.push_back(Event::AddSub(Box::new(sub_data)));
+                            self.events
+                                .push_back(Event::AddSub(data_group.session, Box::new(sub_data)));
                         } else {
                             self.events.push_back(Event::DelSub(self.selected_sub_id));
                         }
@@ -305,6 +369,88 @@ impl PageSub {
                         }
                     });
                 ui.end_row();
+
+                ui.label("session:");
+                ui.add_enabled_ui(!data_group.subscribed, |ui| {
+                    session_selector(ui, "page_sub_session", &mut data_group.session, sessions);
+                });
+                ui.end_row();
+
+                ui.label("queue:")
+                    .on_hover_text("samples are sent to the gui in batches");
+                ui.add_enabled_ui(!data_group.subscribed, |ui| {
+                    ui.horizontal(|ui| {
+                        let queue = &mut data_group.queue;
+                        ui.add(
+                            DragValue::new(&mut queue.queue_size)
+                                .range(1..=1_000_000)
+                                .prefix("size: "),
+                        );
+                        ui.add(
+                            DragValue::new(&mut queue.batch_period)
+                                .range(1..=10_000)
+                                .prefix("batch: ")
+                                .suffix(" ms"),
+                        );
+                        ComboBox::new("sub_drop_policy", "")
+                            .selected_text(queue.drop_policy.as_ref())
+                            .show_ui(ui, |ui| {
+                                for option in DropPolicy::iter() {
+                                    ui.selectable_value(
+                                        &mut queue.drop_policy,
+                                        option,
+                                        option.as_ref(),
+                                    );
+                                }
+                            });
+                        if queue.drop_policy == DropPolicy::SampleEvery {
+                            ui.add(
+                                DragValue::new(&mut queue.sample_every)
+                                    .range(1..=1_000_000)
+                                    .prefix("1/"),
+                            );
+                        }
+                    });
+                });
+                ui.end_row();
+
+                ui.label("dropped:");
+                let text = RichText::new(data_group.dropped.to_string()).monospace();
+                if data_group.dropped > 0 {
+                    ui.label(text.color(Color32::from_rgb(220, 140, 0)));
+                } else {
+                    ui.label(text);
+                }
+                ui.end_row();
+
+                ui.label("advanced:")
+                    .on_hover_text("zenoh-ext advanced subscriber");
+                ui.add_enabled_ui(!data_group.subscribed, |ui| {
+                    ui.checkbox(&mut data_group.advanced.enabled, "");
+                });
+                ui.end_row();
+
+                if data_group.advanced.enabled {
+                    show_advanced_options(ui, &mut data_group.advanced, data_group.subscribed);
+
+                    ui.label("misses:")
+                        .on_hover_text("missed samples detected per publisher");
+                    if data_group.misses.is_empty() {
+                        ui.label("-");
+                    } else {
+                        ui.vertical(|ui| {
+                            for ((zid, eid), missed) in &data_group.misses {
+                                let text = format!("{zid}:{eid}  {missed}");
+                                ui.label(
+                                    RichText::new(text)
+                                        .monospace()
+                                        .color(Color32::from_rgb(220, 140, 0)),
+                                );
+                            }
+                        });
+                    }
+                    ui.end_row();
+                }
             });
 
         if let Some(e) = &data_group.err_str {
@@ -470,9 +616,16 @@ impl PageSub {
         });
     }
 
-    pub fn processing_sub_cb(&mut self, id: u64, sample: Sample, receipt_time: SystemTime) {
-        let key = sample.key_expr().to_string();
-        if let Some(data_group) = self.sub_data_group.get_mut(&id) {
+    pub fn processing_sub_cb(&mut self, id: u64, samples: Vec<(Sample, SystemTime)>, dropped: u64) {
+        let data_group = match self.sub_data_group.get_mut(&id) {
+            None => {
+                return;
+            }
+            Some(o) => o,
+        };
+        data_group.dropped = dropped;
+        for (sample, receipt_time) in samples {
+            let key = sample.key_expr().to_string();
             if let Some(sv) = data_group.map.get_mut(&key) {
                 sv.add_data(sample, receipt_time);
             } else {
@@ -484,6 +637,39 @@ impl PageSub {
         }
     }
 
+    // 会话重新打开后, 重新声明之前的订阅
+    pub fn redeclare(&mut self, ids: &[u64]) {
+        for id in ids {
+            let data_group = match self.sub_data_group.get_mut(id) {
+                None => continue,
+                Some(o) => o,
+            };
+            if data_group.subscribed {
+                continue;
+            }
+            match data_group.sub_data(*id) {
+                Ok(sub_data) => {
+                    data_group.err_str = None;
+                    data_group.subscribed = true;
+                    self.events
+                        .push_back(Event::AddSub(data_group.session, Box::new(sub_data)));
+                }
+                Err(e) => {
+                    data_group.err_str = Some(e);
+                }
+            }
+        }
+    }
+
+    pub fn processing_sub_miss(&mut self, id: u64, miss: SampleMiss) {
+        if let Some(data_group) = self.sub_data_group.get_mut(&id) {
+            *data_group
+                .misses
+                .entry((miss.source_zid, miss.source_eid))
+                .or_default() += miss.missed as u64;
+        }
+    }
+
     pub fn processing_del_sub_res(&mut self, id: u64) {
         if let Some(data_group) = self.sub_data_group.get_mut(&id) {
             data_group.subscribed = false;
@@ -506,6 +692,56 @@ impl PageSub {
     }
 }
 
+fn show_advanced_options(ui: &mut Ui, advanced: &mut AdvancedOptions, subscribed: bool) {
+    ui.label("history:")
+        .on_hover_text("query history from publishers with a cache");
+    ui.add_enabled_ui(!subscribed, |ui| {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut advanced.history, "");
+            ui.add_enabled_ui(advanced.history, |ui| {
+                ui.checkbox(&mut advanced.limit_samples, "max samples");
+                ui.add_enabled(
+                    advanced.limit_samples,
+                    DragValue::new(&mut advanced.max_samples).range(1..=1_000_000),
+                );
+                ui.checkbox(&mut advanced.limit_age, "max age");
+                ui.add_enabled(
+                    advanced.limit_age,
+                    DragValue::new(&mut advanced.max_age)
+                        .range(0.001..=86400.0)
+                        .suffix(" s"),
+                );
+                ui.checkbox(&mut advanced.detect_late_publishers, "late publishers")
+                    .on_hover_text("detect late joiner publishers and query their history");
+            });
+        });
+    });
+    ui.end_row();
+
+    ui.label("recovery:")
+        .on_hover_text("ask for retransmission of missed samples");
+    ui.add_enabled_ui(!subscribed, |ui| {
+        ui.horizontal(|ui| {
+            ComboBox::new("sub_recovery", "")
+                .selected_text(advanced.recovery.as_ref())
+                .show_ui(ui, |ui| {
+                    for option in Recovery::iter() {
+                        ui.selectable_value(&mut advanced.recovery, option, option.as_ref());
+                    }
+                });
+            if advanced.recovery == Recovery::PeriodicQueries {
+                ui.add(
+                    DragValue::new(&mut advanced.query_period)
+                        .range(10..=600_000)
+                        .prefix("period: ")
+                        .suffix(" ms"),
+                );
+            }
+        });
+    });
+    ui.end_row();
+}
+
 #[derive(Hash)]
 struct DndItem {
     key_id: u64,
@@ -631,6 +867,11 @@ pub struct PageSubData {
     err_str: Option<String>,
     selected_key: String,
     selected_origin: ZLocality,
+    session: u64, // 配置的 id, 0 表示默认 session
+    queue: SubQueueOptions,
+    dropped: u64,
+    advanced: AdvancedOptions,
+    misses: BTreeMap<(String, u32), u64>, // <(source zid, source eid), missed>
     filtered: bool,
     filter_str: String,
     buffer_size_tmp: u32,
@@ -642,11 +883,15 @@ pub struct PageSubData {
 
 impl From<&PageSubData> for PageSubData {
     fn from(value: &PageSubData) -> Self {
-        PageSubData::new(
+        let mut d = PageSubData::new(
             value.name.clone(),
             value.key_expr.clone(),
             value.selected_origin,
-        )
+        );
+        d.session = value.session;
+        d.queue = value.queue;
+        d.advanced = value.advanced;
+        d
     }
 }
 
@@ -656,6 +901,9 @@ impl From<&PageSubData> for ArchivePageSubData {
             name: value.name.clone(),
             key_expr: value.key_expr.clone(),
             origin: value.selected_origin,
+            session_id: value.session,
+            queue: value.queue,
+            advanced: value.advanced,
         }
     }
 }
@@ -664,11 +912,11 @@ impl TryFrom<&ArchivePageSubData> for PageSubData {
     type Error = String;
 
     fn try_from(value: &ArchivePageSubData) -> Result<Self, Self::Error> {
-        Ok(PageSubData::new(
-            value.name.clone(),
-            value.key_expr.clone(),
-            value.origin,
-        ))
+        let mut d = PageSubData::new(value.name.clone(), value.key_expr.clone(), value.origin);
+        d.session = value.session_id;
+        d.queue = value.queue;
+        d.advanced = value.advanced;
+        Ok(d)
     }
 }
 
@@ -676,7 +924,11 @@ impl TryFrom<ArchivePageSubData> for PageSubData {
     type Error = String;
 
     fn try_from(value: ArchivePageSubData) -> Result<Self, Self::Error> {
-        Ok(PageSubData::new(value.name, value.key_expr, value.origin))
+        let mut d = PageSubData::new(value.name, value.key_expr, value.origin);
+        d.session = value.session_id;
+        d.queue = value.queue;
+        d.advanced = value.advanced;
+        Ok(d)
     }
 }
 
@@ -689,6 +941,11 @@ impl PageSubData {
             err_str: None,
             selected_key: "".to_string(),
             selected_origin: origin,
+            session: 0,
+            queue: SubQueueOptions::default(),
+            dropped: 0,
+            advanced: AdvancedOptions::default(),
+            misses: BTreeMap::new(),
             filtered: false,
             filter_str: "".to_string(),
             buffer_size_tmp: VALUE_BUFFER_SIZE_DEFAULT as u32,
@@ -699,6 +956,49 @@ impl PageSubData {
         }
     }
 
+    fn sub_data(&self, id: u64) -> Result<SubData, String> {
+        let key_expr_str = self.key_expr.replace(&[' ', '\t', '\n', '\r'], "");
+        if key_expr_str.is_empty() {
+            return Err("key expr is empty".to_string());
+        }
+        let key = OwnedKeyExpr::from_str(key_expr_str.as_str()).map_err(|e| e.to_string())?;
+
+        let queue = self.queue;
+        let drop_policy = match queue.drop_policy {
+            DropPolicy::DropOldest => SubDropPolicy::DropOldest,
+            DropPolicy::KeepLatest => SubDropPolicy::KeepLatest,
+            DropPolicy::SampleEvery => SubDropPolicy::SampleEvery(queue.sample_every),
+        };
+
+        let advanced = self.advanced;
+        let advanced = if advanced.enabled {
+            let history = advanced.history.then(|| SubHistory {
+                max_samples: advanced.limit_samples.then_some(advanced.max_samples),
+                max_age: advanced.limit_age.then_some(advanced.max_age),
+                detect_late_publishers: advanced.detect_late_publishers,
+            });
+            let recovery = match advanced.recovery {
+                Recovery::Disabled => SubRecovery::Disabled,
+                Recovery::Heartbeat => SubRecovery::Heartbeat,
+                Recovery::PeriodicQueries => {
+                    SubRecovery::PeriodicQueries(Duration::from_millis(advanced.query_period))
+                }
+            };
+            Some(AdvancedSubData { history, recovery })
+        } else {
+            None
+        };
+        Ok(SubData {
+            id,
+            key_expr: key,
+            origin: self.selected_origin.into(),
+            queue_size: queue.queue_size,
+            batch_period: Duration::from_millis(queue.batch_period),
+            drop_policy,
+            advanced,
+        })
+    }
+
     fn update_tree(&mut self) {
         let keys = self.map.keys().cloned().collect();
         let keys = if self.filtered {
@@ -727,7 +1027,7 @@ impl Tree {
     }
 
     pub fn show_ui(&self, selected_key: &mut String, ui: &mut Ui) {
-        for (_, index_top) in &self.index_top_node {
+        for index_top in self.index_top_node.values() {
             let top_node: &TreeNode = self.mem.get((*index_top) as usize).unwrap();
             top_node.show_ui(self, selected_key, ui);
         }
@@ -829,7 +1129,7 @@ impl TreeNode {
         CollapsingHeader::new(name)
             .default_open(false)
             .show(ui, |ui| {
-                for (_, index_child) in &self.index_children {
+                for index_child in self.index_children.values() {
                     let child: &TreeNode = tree.mem.get((*index_child) as usize).unwrap();
                     child.show_ui(tree, selected_key, ui);
                 }
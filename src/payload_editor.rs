@@ -719,7 +719,7 @@ enum Page {
     Hex,
 }
 
-fn parse_str_to_vec(s: &str) -> Result<Vec<u8>, String> {
+pub(crate) fn parse_str_to_vec(s: &str) -> Result<Vec<u8>, String> {
     let buf = s.replace(&[' ', '\t', '\n', '\r'], "");
     decode(buf.as_bytes()).map_err(|e| e.to_string())
 }
@@ -0,0 +1,164 @@
+use eframe::egui::{CollapsingHeader, Color32, Grid, RichText, Ui};
+use std::sync::Arc;
+use zenoh::bytes::Encoding;
+
+use crate::{data_viewer::DataViewer, hex_viewer::HexViewer, task_zenoh::QueryRecord};
+
+#[derive(Eq, PartialEq, Copy, Clone)]
+enum QueryViewerPage {
+    Raw,
+    Parse,
+}
+
+pub struct QueryViewer {
+    selected_page: QueryViewerPage,
+    query_info: QueryInfo,
+    hex_viewer: HexViewer,
+    data_viewer: DataViewer,
+}
+
+impl Default for QueryViewer {
+    fn default() -> Self {
+        QueryViewer {
+            selected_page: QueryViewerPage::Raw,
+            query_info: QueryInfo::default(),
+            hex_viewer: HexViewer::new(Arc::new(Vec::new())),
+            data_viewer: DataViewer::Bin,
+        }
+    }
+}
+
+impl QueryViewer {
+    pub fn new_from_query_record(record: &QueryRecord) -> Self {
+        let query_info = QueryInfo::new_from(record);
+        let data = match &record.value {
+            None => Vec::new(),
+            Some((_, payload)) => payload.to_bytes().to_vec(),
+        };
+        let arc_data = Arc::new(data);
+        let data_viewer = DataViewer::load(&query_info.encoding, arc_data.as_slice());
+        let hex_viewer = HexViewer::new(arc_data);
+
+        QueryViewer {
+            selected_page: QueryViewerPage::Parse,
+            query_info,
+            hex_viewer,
+            data_viewer,
+        }
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        self.query_info.show(ui);
+
+        ui.separator();
+
+        self.show_tab_label(ui);
+
+        ui.add_space(10.0);
+
+        match self.selected_page {
+            QueryViewerPage::Raw => {
+                self.hex_viewer.show(ui);
+            }
+            QueryViewerPage::Parse => {
+                self.data_viewer.show(ui);
+            }
+        }
+    }
+
+    fn show_tab_label(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .selectable_label(self.selected_page == QueryViewerPage::Parse, "parse")
+                .clicked()
+            {
+                self.selected_page = QueryViewerPage::Parse;
+            }
+
+            if ui
+                .selectable_label(self.selected_page == QueryViewerPage::Raw, "raw")
+                .clicked()
+            {
+                self.selected_page = QueryViewerPage::Raw;
+            }
+        });
+    }
+}
+
+#[derive(Default)]
+struct QueryInfo {
+    key: String,
+    parameters: String,
+    attachment: Option<Vec<u8>>,
+    payload: bool,
+    encoding: Encoding,
+    reply_result: Option<Result<(), String>>,
+}
+
+impl QueryInfo {
+    fn new_from(record: &QueryRecord) -> Self {
+        let (payload, encoding) = match &record.value {
+            None => (false, Encoding::default()),
+            Some((encoding, _)) => (true, encoding.clone()),
+        };
+
+        QueryInfo {
+            key: record.key_expr.clone(),
+            parameters: record.parameters.clone(),
+            attachment: record.attachment.as_ref().map(|a| a.to_bytes().to_vec()),
+            payload,
+            encoding,
+            reply_result: Some(record.reply_result.clone()),
+        }
+    }
+
+    fn show(&mut self, ui: &mut Ui) {
+        let show_ui = |ui: &mut Ui| {
+            ui.label("key:");
+            ui.label(RichText::new(self.key.as_str()).monospace());
+            ui.end_row();
+
+            ui.label("parameters:");
+            ui.label(RichText::new(self.parameters.as_str()).monospace());
+            ui.end_row();
+
+            ui.label("attachment:");
+            let s = match &self.attachment {
+                None => "-".to_string(),
+                Some(attachment) => String::from_utf8(attachment.clone())
+                    .unwrap_or(format!("{:?}", attachment.as_slice())),
+            };
+            ui.label(RichText::new(s).monospace());
+            ui.end_row();
+
+            ui.label("encoding:");
+            let s = if self.payload {
+                self.encoding.to_string()
+            } else {
+                "-".to_string()
+            };
+            ui.label(RichText::new(s).monospace());
+            ui.end_row();
+
+            if let Some(reply_result) = &self.reply_result {
+                ui.label("reply:");
+                let text = match reply_result {
+                    Ok(_) => RichText::new("ok").monospace(),
+                    Err(e) => RichText::new(e).monospace().color(Color32::RED),
+                };
+                ui.label(text);
+                ui.end_row();
+            }
+        };
+
+        CollapsingHeader::new("Query info")
+            .default_open(true)
+            .show(ui, |ui| {
+                Grid::new("query_viewer_base_info_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        show_ui(ui);
+                    });
+            });
+    }
+}
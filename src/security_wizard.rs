@@ -0,0 +1,427 @@
+use eframe::egui::{
+    Button, Checkbox, CollapsingHeader, Color32, Grid, RichText, TextEdit, TextStyle, Ui, Widget,
+};
+use egui_file::{DialogType, FileDialog};
+use std::{fs, io::BufReader, path::PathBuf};
+
+const TLS_KEY: &str = "transport/link/tls";
+const USRPWD_KEY: &str = "transport/auth/usrpwd";
+
+#[derive(Eq, PartialEq, Copy, Clone)]
+enum PemFile {
+    RootCa,
+    Certificate,
+    PrivateKey,
+}
+
+// 生成 TLS/QUIC 和 usrpwd 的配置, 结果作为 overrides 写入配置
+// password 不写入 overrides, 只保存在内存中, 不会保存到工作区文件
+pub struct SecurityWizard {
+    endpoint: String,
+    root_ca: String,
+    mtls: bool,
+    certificate: String,
+    private_key: String,
+    verify_name: bool,
+    user: String,
+    password: String,
+    applied_password: Option<String>, // apply 后打开 session 时使用
+    err_str: Option<String>,
+    file_dialog: Option<(PemFile, FileDialog)>,
+}
+
+impl Default for SecurityWizard {
+    fn default() -> Self {
+        SecurityWizard {
+            endpoint: String::new(),
+            root_ca: String::new(),
+            mtls: false,
+            certificate: String::new(),
+            private_key: String::new(),
+            verify_name: true,
+            user: String::new(),
+            password: String::new(),
+            applied_password: None,
+            err_str: None,
+            file_dialog: None,
+        }
+    }
+}
+
+impl SecurityWizard {
+    // 返回需要写入的 overrides, (key, json5 value)
+    pub fn show(&mut self, ui: &mut Ui, enabled: bool) -> Option<Vec<(String, String)>> {
+        let mut r = None;
+        CollapsingHeader::new("Security")
+            .id_salt("page_session_security")
+            .default_open(false)
+            .show(ui, |ui| {
+                Grid::new("page_session_security_grid")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label("endpoint");
+                        TextEdit::singleline(&mut self.endpoint)
+                            .desired_width(3000.0)
+                            .font(TextStyle::Monospace)
+                            .hint_text("tls/localhost:7447 or quic/localhost:7447, optional")
+                            .interactive(enabled)
+                            .ui(ui)
+                            .on_hover_text("replaces all connect/endpoints of the config");
+                        ui.end_row();
+
+                        self.show_pem_row(ui, PemFile::RootCa, enabled);
+
+                        ui.label("mutual TLS");
+                        ui.add_enabled(enabled, Checkbox::without_text(&mut self.mtls));
+                        ui.end_row();
+
+                        self.show_pem_row(ui, PemFile::Certificate, enabled && self.mtls);
+                        self.show_pem_row(ui, PemFile::PrivateKey, enabled && self.mtls);
+
+                        ui.label("verify server name");
+                        ui.add_enabled(enabled, Checkbox::without_text(&mut self.verify_name));
+                        ui.end_row();
+
+                        ui.label("user");
+                        TextEdit::singleline(&mut self.user)
+                            .desired_width(3000.0)
+                            .font(TextStyle::Monospace)
+                            .hint_text("transport.auth.usrpwd, optional")
+                            .interactive(enabled)
+                            .ui(ui);
+                        ui.end_row();
+
+                        ui.label("password");
+                        TextEdit::singleline(&mut self.password)
+                            .desired_width(3000.0)
+                            .font(TextStyle::Monospace)
+                            .password(true)
+                            .interactive(enabled)
+                            .ui(ui);
+                        ui.end_row();
+                    });
+
+                ui.label(
+                    RichText::new(
+                        "the password is kept in memory only, it is not saved in the workspace file",
+                    )
+                    .weak(),
+                );
+
+                if let Some(e) = &self.err_str {
+                    ui.label(RichText::new(e).color(Color32::RED));
+                }
+
+                if ui
+                    .add_enabled(enabled, Button::new("apply"))
+                    .on_hover_text("check the files and write the settings into the overrides")
+                    .clicked()
+                {
+                    match self.overrides() {
+                        Ok(o) => {
+                            self.err_str = None;
+                            self.applied_password = (!self.user.trim().is_empty())
+                                .then(|| self.password.clone());
+                            r = Some(o);
+                        }
+                        Err(e) => {
+                            self.err_str = Some(e);
+                        }
+                    }
+                }
+            });
+
+        let mut selected = None;
+        if let Some((pem_file, dialog)) = &mut self.file_dialog {
+            if dialog.show(ui.ctx()).selected() {
+                if let DialogType::OpenFile = dialog.dialog_type() {
+                    if let Some(p) = dialog.path() {
+                        selected = Some((*pem_file, p.to_string_lossy().to_string()));
+                    }
+                }
+            }
+        }
+        if let Some((pem_file, s)) = selected {
+            *self.path_mut(pem_file) = s;
+        }
+
+        r
+    }
+
+    fn show_pem_row(&mut self, ui: &mut Ui, pem_file: PemFile, enabled: bool) {
+        let (label, hint) = match pem_file {
+            PemFile::RootCa => ("root CA", "ca.pem"),
+            PemFile::Certificate => ("client certificate", "client.pem"),
+            PemFile::PrivateKey => ("client private key", "client-key.pem"),
+        };
+        ui.label(label);
+        ui.horizontal(|ui| {
+            if ui.add_enabled(enabled, Button::new("..")).clicked() {
+                let path = self.path_mut(pem_file).clone();
+                let path = (!path.is_empty()).then(|| PathBuf::from(path));
+                let mut dialog = FileDialog::open_file(path)
+                    .show_new_folder(false)
+                    .show_rename(false);
+                dialog.open();
+                self.file_dialog = Some((pem_file, dialog));
+            }
+            TextEdit::singleline(self.path_mut(pem_file))
+                .desired_width(3000.0)
+                .font(TextStyle::Monospace)
+                .hint_text(hint)
+                .interactive(enabled)
+                .ui(ui);
+        });
+        ui.end_row();
+    }
+
+    fn path_mut(&mut self, pem_file: PemFile) -> &mut String {
+        match pem_file {
+            PemFile::RootCa => &mut self.root_ca,
+            PemFile::Certificate => &mut self.certificate,
+            PemFile::PrivateKey => &mut self.private_key,
+        }
+    }
+
+    // usrpwd 的 password, 与 overrides 一起在打开 session 时使用
+    pub fn password_override(&self) -> Option<(String, String)> {
+        let password = self.applied_password.as_ref()?;
+        let value = serde_json::to_string(password).unwrap_or_default();
+        Some((format!("{USRPWD_KEY}/password"), value))
+    }
+
+    // 工作区中保存了 usrpwd 的 user, password 只在内存中, 重新加载工作区后需要重新输入
+    pub fn password_required(&self, keys: &[&str]) -> bool {
+        let has_key = |k: &str| keys.contains(&format!("{USRPWD_KEY}/{k}").as_str());
+        self.applied_password.is_none() && has_key("user") && !has_key("password")
+    }
+
+    // 不包含 password, 见 password_override
+    fn overrides(&self) -> Result<Vec<(String, String)>, String> {
+        let json = |s: &str| serde_json::to_string(s).unwrap_or_default();
+        let mut r = Vec::new();
+
+        let endpoint = self.endpoint.trim();
+        if !endpoint.is_empty() {
+            if !endpoint.starts_with("tls/") && !endpoint.starts_with("quic/") {
+                return Err("endpoint should start with \"tls/\" or \"quic/\"".to_string());
+            }
+            r.push((
+                "connect/endpoints".to_string(),
+                format!("[{}]", json(endpoint)),
+            ));
+        }
+
+        // 只设置 usrpwd 时不修改配置中的 TLS 设置
+        let tls = !endpoint.is_empty() || !self.root_ca.trim().is_empty() || self.mtls;
+
+        // 没有指定 root CA 时使用系统的证书
+        if !self.root_ca.trim().is_empty() {
+            let root_ca = check_pem(self.root_ca.as_str(), PemFile::RootCa)?;
+            r.push((
+                format!("{TLS_KEY}/root_ca_certificate"),
+                json(root_ca.as_str()),
+            ));
+        }
+        if tls {
+            r.push((format!("{TLS_KEY}/enable_mtls"), self.mtls.to_string()));
+        }
+        if self.mtls {
+            let certificate = check_pem(self.certificate.as_str(), PemFile::Certificate)?;
+            let private_key = check_pem(self.private_key.as_str(), PemFile::PrivateKey)?;
+            r.push((
+                format!("{TLS_KEY}/connect_certificate"),
+                json(certificate.as_str()),
+            ));
+            r.push((
+                format!("{TLS_KEY}/connect_private_key"),
+                json(private_key.as_str()),
+            ));
+        }
+        if tls {
+            r.push((
+                format!("{TLS_KEY}/verify_name_on_connect"),
+                self.verify_name.to_string(),
+            ));
+        }
+
+        let user = self.user.trim();
+        if !user.is_empty() || !self.password.is_empty() {
+            // zenoh 要求 user 和 password 同时设置
+            if user.is_empty() || self.password.is_empty() {
+                return Err("usrpwd needs both user and password".to_string());
+            }
+            r.push((format!("{USRPWD_KEY}/user"), json(user)));
+        }
+        Ok(r)
+    }
+}
+
+// 检查文件存在并且包含需要的 PEM 内容, 返回文件路径
+fn check_pem(path: &str, pem_file: PemFile) -> Result<String, String> {
+    let name = match pem_file {
+        PemFile::RootCa => "root CA",
+        PemFile::Certificate => "client certificate",
+        PemFile::PrivateKey => "client private key",
+    };
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(format!("{name}: no file path"));
+    }
+    let file = fs::File::open(path).map_err(|e| format!("{name}: {e}"))?;
+    let mut reader = BufReader::new(file);
+    match pem_file {
+        PemFile::RootCa | PemFile::Certificate => {
+            let certs = rustls_pemfile::certs(&mut reader)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("{name}: invalid PEM, {e}"))?;
+            if certs.is_empty() {
+                return Err(format!("{name}: no certificate found in the PEM file"));
+            }
+        }
+        PemFile::PrivateKey => {
+            let key = rustls_pemfile::private_key(&mut reader)
+                .map_err(|e| format!("{name}: invalid PEM, {e}"))?;
+            if key.is_none() {
+                return Err(format!("{name}: no private key found in the PEM file"));
+            }
+        }
+    }
+    Ok(path.to_string())
+}
+
+#[cfg(test)]
+fn test_pem_path(name: &str) -> String {
+    // 每个测试使用不同的文件名, 并行测试时不会相互覆盖
+    let file_name = format!("hammer_security_wizard_{}_{name}.pem", std::process::id());
+    std::env::temp_dir()
+        .join(file_name)
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+fn write_test_pem(name: &str, label: &str) -> String {
+    let path = test_pem_path(name);
+    let content = format!("-----BEGIN {label}-----\nAAECAwQFBgcICQ==\n-----END {label}-----\n");
+    fs::write(path.as_str(), content).unwrap();
+    path
+}
+
+#[test]
+fn security_wizard_check_pem() {
+    let ca = write_test_pem("check_ca", "CERTIFICATE");
+    let key = write_test_pem("check_key", "PRIVATE KEY");
+    assert_eq!(check_pem(ca.as_str(), PemFile::RootCa), Ok(ca.clone()));
+    assert_eq!(
+        check_pem(key.as_str(), PemFile::PrivateKey),
+        Ok(key.clone())
+    );
+    assert_eq!(
+        check_pem(key.as_str(), PemFile::RootCa),
+        Err("root CA: no certificate found in the PEM file".to_string())
+    );
+    assert_eq!(
+        check_pem(ca.as_str(), PemFile::PrivateKey),
+        Err("client private key: no private key found in the PEM file".to_string())
+    );
+    assert_eq!(
+        check_pem(" ", PemFile::Certificate),
+        Err("client certificate: no file path".to_string())
+    );
+    assert!(check_pem(test_pem_path("missing").as_str(), PemFile::RootCa).is_err());
+    let _ = fs::remove_file(ca);
+    let _ = fs::remove_file(key);
+}
+
+#[test]
+fn security_wizard_overrides() {
+    let keys = |w: &SecurityWizard| -> Vec<String> {
+        w.overrides().unwrap().into_iter().map(|(k, _)| k).collect()
+    };
+
+    let mut w = SecurityWizard {
+        endpoint: "tcp/localhost:7447".to_string(),
+        ..SecurityWizard::default()
+    };
+    assert_eq!(
+        w.overrides(),
+        Err("endpoint should start with \"tls/\" or \"quic/\"".to_string())
+    );
+
+    // TLS
+    w.endpoint = "tls/localhost:7447".to_string();
+    w.root_ca = write_test_pem("overrides_ca", "CERTIFICATE");
+    let r = w.overrides().unwrap();
+    assert_eq!(
+        r[0],
+        (
+            "connect/endpoints".to_string(),
+            r#"["tls/localhost:7447"]"#.to_string()
+        )
+    );
+    assert_eq!(
+        keys(&w),
+        vec![
+            "connect/endpoints",
+            "transport/link/tls/root_ca_certificate",
+            "transport/link/tls/enable_mtls",
+            "transport/link/tls/verify_name_on_connect",
+        ]
+    );
+
+    // mTLS
+    w.mtls = true;
+    assert!(w.overrides().is_err());
+    w.certificate = write_test_pem("overrides_cert", "CERTIFICATE");
+    w.private_key = write_test_pem("overrides_key", "PRIVATE KEY");
+    assert_eq!(
+        keys(&w),
+        vec![
+            "connect/endpoints",
+            "transport/link/tls/root_ca_certificate",
+            "transport/link/tls/enable_mtls",
+            "transport/link/tls/connect_certificate",
+            "transport/link/tls/connect_private_key",
+            "transport/link/tls/verify_name_on_connect",
+        ]
+    );
+    for path in [&w.root_ca, &w.certificate, &w.private_key] {
+        let _ = fs::remove_file(path);
+    }
+
+    // 只设置 usrpwd 时不包含 TLS 的配置, password 也不在 overrides 中
+    let mut w = SecurityWizard {
+        user: "user".to_string(),
+        ..SecurityWizard::default()
+    };
+    assert_eq!(
+        w.overrides(),
+        Err("usrpwd needs both user and password".to_string())
+    );
+    w.password = "password".to_string();
+    assert_eq!(keys(&w), vec!["transport/auth/usrpwd/user"]);
+    assert_eq!(w.password_override(), None);
+    assert!(w.password_required(&["transport/auth/usrpwd/user"]));
+    assert!(!w.password_required(&[
+        "transport/auth/usrpwd/user",
+        "transport/auth/usrpwd/password"
+    ]));
+    assert!(!w.password_required(&["connect/endpoints"]));
+    w.applied_password = Some(w.password.clone());
+    assert!(!w.password_required(&["transport/auth/usrpwd/user"]));
+    assert_eq!(
+        w.password_override(),
+        Some((
+            "transport/auth/usrpwd/password".to_string(),
+            r#""password""#.to_string()
+        ))
+    );
+
+    w.user.clear();
+    assert_eq!(
+        w.overrides(),
+        Err("usrpwd needs both user and password".to_string())
+    );
+}
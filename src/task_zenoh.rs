@@ -1,40 +1,256 @@
-use flume::{unbounded, RecvError, TryRecvError};
+use eframe::egui::Context;
+use flume::{unbounded, RecvError, SendError};
 use log::{error, info, warn};
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, VecDeque},
+    mem,
+    ops::Deref,
     path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
     thread,
-    time::{Duration, SystemTime},
+    time::{Duration, Instant, SystemTime},
 };
-use tokio::{runtime::Runtime, select, task, time::sleep};
+use tokio::{
+    runtime::Runtime,
+    select, task,
+    time::{interval, sleep, MissedTickBehavior},
+};
+use uhlc::NTP64;
 use zenoh::query::{Parameters, Selector};
 use zenoh::{
     bytes::{Encoding, ZBytes},
+    config::{WhatAmI, WhatAmIMatcher},
     handlers::FifoChannelHandler,
     key_expr::OwnedKeyExpr,
-    pubsub::Subscriber,
-    qos::{CongestionControl, Priority},
-    query::{QueryConsolidation, QueryTarget, Reply},
-    sample::{Locality, Sample},
-    Config, Session,
+    liveliness::LivelinessToken,
+    matching::{MatchingListener, MatchingStatus},
+    pubsub::{Publisher, Subscriber},
+    qos::{CongestionControl, Priority, Reliability},
+    query::{
+        Querier, Query, QueryConsolidation, QueryTarget, Queryable, Reply, ReplyError, ReplyKeyExpr,
+    },
+    sample::{Locality, Sample, SampleKind},
+    shm::{GarbageCollect, PosixShmProviderBackendBinaryHeap, ShmProvider, ShmProviderBuilder},
+    time::Timestamp,
+    Config, Session, Wait,
+};
+use zenoh_ext::{
+    AdvancedPublisher, AdvancedPublisherBuilderExt, AdvancedSubscriberBuilderExt, CacheConfig,
+    HistoryConfig, Miss, MissDetectionConfig, RecoveryConfig, SampleMissListener,
 };
 
 pub type Sender<T> = flume::Sender<T>;
 pub type Receiver<T> = flume::Receiver<T>;
 
+// 发送消息到 gui 后请求重绘, gui 不需要定时轮询
+#[derive(Clone)]
+pub struct SenderToGui {
+    sender: Sender<MsgZenohToGui>,
+    ctx: Context,
+}
+
+impl SenderToGui {
+    pub fn send(&self, msg: MsgZenohToGui) -> Result<(), SendError<MsgZenohToGui>> {
+        self.sender.send(msg)?;
+        self.ctx.request_repaint();
+        Ok(())
+    }
+}
+
+pub enum SessionConfig {
+    File(PathBuf),
+    Json5(String), // 没有保存到文件的配置, 例如 scout 生成的
+}
+
+pub type ConfigOverrides = Vec<(String, String)>; // (key, json5 value)
+
+pub struct ScoutData {
+    pub what: WhatAmIMatcher,
+    pub multicast_address: String,
+    pub multicast_interface: String,
+    pub duration: Duration,
+}
+
+pub struct EmbeddedNodeData {
+    pub mode: WhatAmI,
+    pub listen: String,
+}
+
+#[derive(Clone)]
+pub struct HelloInfo {
+    pub zid: String,
+    pub whatami: String,
+    pub locators: Vec<String>,
+}
+
 pub struct SubData {
     pub id: u64,
     pub key_expr: OwnedKeyExpr,
     pub origin: Locality,
+    pub queue_size: usize,      // 每个批次最多发送到 gui 的数据数量
+    pub batch_period: Duration, // 批量发送到 gui 的周期
+    pub drop_policy: SubDropPolicy,
+    pub advanced: Option<AdvancedSubData>, // None 表示普通 subscriber
+}
+
+pub struct AdvancedSubData {
+    pub history: Option<SubHistory>,
+    pub recovery: SubRecovery,
+}
+
+pub struct SubHistory {
+    pub max_samples: Option<usize>,
+    pub max_age: Option<f64>, // 秒
+    pub detect_late_publishers: bool,
+}
+
+#[derive(Copy, Clone)]
+pub enum SubRecovery {
+    Disabled,
+    Heartbeat,
+    PeriodicQueries(Duration),
+}
+
+#[derive(Clone)]
+pub struct SampleMiss {
+    pub source_zid: String,
+    pub source_eid: u32,
+    pub missed: u32,
+}
+
+pub type SampleBatch = Vec<(Sample, SystemTime)>; // (value, timestamp)
+
+#[derive(Copy, Clone)]
+pub enum SubDropPolicy {
+    DropOldest,       // 队列满时丢弃最旧的数据
+    KeepLatest,       // 每个 key 只保留最新的数据, key 数量满时丢弃最久没有更新的 key
+    SampleEvery(u64), // 每 N 个数据保留一个, 队列满时丢弃最旧的数据
 }
 
 pub struct PutData {
     pub id: u64,
     pub key: OwnedKeyExpr,
+    pub kind: SampleKind,
     pub congestion_control: CongestionControl,
     pub priority: Priority,
+    pub express: bool,
+    pub reliability: Reliability,
+    pub destination: Locality,
+    pub attachment: Option<ZBytes>,
+    pub timestamp: PutTimestamp,
     pub encoding: Encoding,
     pub payload: ZBytes,
+    pub shm: bool, // payload 从 shm provider 中分配
+}
+
+#[derive(Clone)]
+pub struct ShmStatus {
+    pub pool_size: usize,
+    pub available: usize,
+    pub error: Option<String>, // 最近一次分配的错误
+}
+
+pub struct PublisherData {
+    pub id: u64,
+    pub key: OwnedKeyExpr,
+    pub congestion_control: CongestionControl,
+    pub priority: Priority,
+    pub express: bool,
+    pub reliability: Reliability,
+    pub destination: Locality,
+    pub advanced: Option<AdvancedPubData>, // None 表示普通 publisher
+}
+
+pub struct AdvancedPubData {
+    pub cache: Option<usize>, // 缓存的数量, None 表示不缓存
+    pub miss_detection: PubMissDetection,
+    pub publisher_detection: bool,
+}
+
+#[derive(Copy, Clone)]
+pub enum PubMissDetection {
+    Disabled,
+    SequenceNumber, // 只发送序列号, 不发送 heartbeat
+    Heartbeat(Duration),
+    SporadicHeartbeat(Duration), // 只在序列号变化时发送 heartbeat
+}
+
+// put 项声明的 publisher
+enum PutPublisher {
+    Plain(Publisher<'static>),
+    Advanced(AdvancedPublisher<'static>),
+}
+
+pub struct PutTaskData {
+    pub put: PutData,
+    pub rate: f64, // Hz, 每秒发送的消息数
+    pub stop: PutTaskStop,
+    pub burst: u64, // 每次连续发送的消息数
+}
+
+#[derive(Copy, Clone)]
+pub enum PutTaskStop {
+    Count(u64),
+    Duration(Duration),
+}
+
+#[derive(Copy, Clone)]
+pub enum PutTaskCtrl {
+    Pause,
+    Resume,
+    Stop,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum PutTaskState {
+    Running,
+    Paused,
+    Finished,
+}
+
+#[derive(Clone)]
+pub struct PutTaskStatus {
+    pub sent: u64,
+    pub failed: u64,
+    pub rate: f64, // 最近一个统计周期内实际达到的速率
+    pub state: PutTaskState,
+    pub last_error: Option<String>,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum BenchMode {
+    Ping,   // 向 ping key 发送, 等待 pong key 的回复, 统计往返时间
+    Pong,   // 将 ping key 上收到的数据原样发送到 pong key
+    ThrPub, // 向 thr key 持续发送
+    ThrSub, // 统计 thr key 上收到的数据
+}
+
+pub struct BenchData {
+    pub mode: BenchMode,
+    pub ping_key: OwnedKeyExpr,
+    pub pong_key: OwnedKeyExpr,
+    pub thr_key: OwnedKeyExpr,
+    pub payload_size: usize,
+    pub congestion_control: CongestionControl,
+    pub priority: Priority,
+    pub express: bool,
+    pub ping_count: u64,
+    pub ping_interval: Duration,
+}
+
+pub enum BenchReport {
+    Rtt(Vec<Duration>, u64),  // (round trip times, lost count)
+    Rate(Duration, u64, u64), // (period, msgs, bytes)
+}
+
+#[derive(Copy, Clone)]
+pub enum PutTimestamp {
+    None,
+    Now,         // 由 session 生成
+    Time(NTP64), // 指定时间, id 使用 session 的 id
 }
 
 pub struct QueryData {
@@ -46,7 +262,98 @@ pub struct QueryData {
     pub consolidation: QueryConsolidation,
     pub locality: Locality,
     pub timeout: Duration,
+    pub accept_replies: ReplyKeyExpr,
+    pub value: Option<(Encoding, ZBytes)>,
+    pub shm: bool, // payload 从 shm provider 中分配
+}
+
+pub struct QuerierData {
+    pub id: u64, // get id
+    pub key_expr: OwnedKeyExpr,
+    pub target: QueryTarget,
+    pub consolidation: QueryConsolidation,
+    pub locality: Locality,
+    pub timeout: Duration,
+    pub accept_replies: ReplyKeyExpr,
+}
+
+#[derive(Clone)]
+pub struct QueryFinish {
+    pub replies: u64,
+    pub elapsed: Duration,
+    pub reason: QueryEndReason,
+}
+
+#[derive(Clone)]
+pub enum QueryEndReason {
+    Completed, // 所有 queryable 已应答
+    Timeout,
+    Cancelled,
+}
+
+pub enum QueryReply {
+    Ok(Encoding, ZBytes),
+    Err(Encoding, ZBytes),
+    Ignore,
+}
+
+pub struct QueryableData {
+    pub id: u64,
+    pub key_expr: OwnedKeyExpr,
+    pub complete: bool,
+    pub origin: Locality,
+    pub reply: QueryReply,
+}
+
+// 收到的 query 的记录, 以及对其应答的结果
+pub struct QueryRecord {
+    pub key_expr: String,
+    pub parameters: String,
+    pub attachment: Option<ZBytes>,
     pub value: Option<(Encoding, ZBytes)>,
+    pub reply_result: Result<(), String>,
+}
+
+pub struct LivelinessSubData {
+    pub key_expr: OwnedKeyExpr,
+    pub history: bool,
+}
+
+/// 根据 session 当前连接的 router 和 peer 判断的状态, 每 SESSION_INFO_PERIOD 更新一次.
+///
+/// - Connected: 至少连接了一个 router 或 peer.
+/// - Degraded: 没有任何连接, session 是 peer 或 router 模式, 并且没有配置 connect endpoint.
+///   这种 session 只能等待被其他节点发现 (scouting 或 listen), 本地的通信仍然可用, 不会自动重新打开.
+/// - Disconnected: 没有任何连接, session 是 client 模式, 或者配置了 connect endpoint 但都没有连上.
+#[derive(Copy, Clone, Eq, PartialEq, Default)]
+pub enum SessionHealth {
+    #[default]
+    Connected,
+    Degraded,
+    Disconnected,
+}
+
+#[derive(Clone, Default)]
+pub struct SessionInfoData {
+    pub zid: String,
+    pub mode: String,
+    pub health: SessionHealth,
+    pub routers: Vec<String>,
+    pub peers: Vec<String>,
+    pub locators: Vec<String>,
+    pub transports: Option<Vec<TransportInfo>>, // None 表示 adminspace 未开启
+}
+
+#[derive(Clone)]
+pub struct TransportInfo {
+    pub zid: String,
+    pub whatami: String,
+    pub links: Vec<(String, String)>, // (src, dst)
+}
+
+pub struct LivelinessGetData {
+    pub key_expr: OwnedKeyExpr,
+    pub timeout: Duration,
 }
 
 pub enum MsgGuiToZenoh {
@@ -54,45 +361,270 @@ pub enum MsgGuiToZenoh {
     AddSubReq(Box<SubData>), // (sub id,key)
     DelSubReq(u64),          // sub id
     GetReq(Box<QueryData>),
+    CancelGetReq(u64), // get id
+    DeclareQuerierReq(Box<QuerierData>),
+    UndeclareQuerierReq(u64), // get id
     PutReq(Box<PutData>),
+    AddQueryableReq(Box<QueryableData>),
+    DelQueryableReq(u64),                      // queryable id
+    DeclareTokenReq(Box<(u64, OwnedKeyExpr)>), // (token id, key expr)
+    UndeclareTokenReq(u64),                    // token id
+    AddLivelinessSubReq(Box<LivelinessSubData>),
+    DelLivelinessSubReq,
+    LivelinessGetReq(Box<LivelinessGetData>),
+    DeclarePublisherReq(Box<PublisherData>),
+    UndeclarePublisherReq(u64), // put id
+    PutTaskReq(Box<PutTaskData>),
+    PutTaskCtrlReq(u64, PutTaskCtrl), // (put id, ctrl)
+    BenchStartReq(Box<BenchData>),
+    BenchStopReq,
 }
 
 pub enum MsgZenohToGui {
     OpenSession(Result<u64, (u64, String)>), // Ok表示成功， Err表示失败
     AddSubRes(Box<(u64, Result<(), String>)>), // sub id, true 表示成功, false表示失败
     DelSubRes(u64),                          // sub id
-    SubCB(Box<(u64, Sample, SystemTime)>),   // (sub id, value, timestamp)
+    SubCB(Box<(u64, SampleBatch, u64)>),     // (sub id, samples, dropped)
+    SubMiss(Box<(u64, SampleMiss)>),         // sub id, advanced subscriber 检测到的丢失
     GetRes(Box<(u64, Reply)>),               // (get id, result, timestamp)
+    GetFinish(Box<(u64, QueryFinish)>),      // get id
+    GetErr(Box<(u64, String)>),              // (get id, error), query 没有发出
+    DeclareQuerierRes(Box<(u64, Result<(), String>)>), // get id
+    UndeclareQuerierRes(u64),                // get id
+    QuerierMatchingStatus(u64, bool),        // (get id, matching)
     PutRes(Box<(u64, bool, String)>),        // true 表示成功， false表示失败
+    AddQueryableRes(Box<(u64, Result<(), String>)>), // queryable id
+    DelQueryableRes(u64),                    // queryable id
+    QueryableCB(Box<(u64, QueryRecord, SystemTime)>), // (queryable id, query, timestamp)
+    DeclareTokenRes(Box<(u64, Result<(), String>)>), // token id
+    UndeclareTokenRes(u64),                  // token id
+    AddLivelinessSubRes(Result<(), String>),
+    DelLivelinessSubRes,
+    LivelinessSubCB(Box<(Sample, SystemTime)>), // (value, timestamp)
+    LivelinessGetRes(Box<(Reply, SystemTime)>), // (result, timestamp)
+    LivelinessGetFinish(Result<(), String>),
+    DeclarePublisherRes(Box<(u64, Result<(), String>)>), // put id
+    UndeclarePublisherRes(u64),                          // put id
+    PublisherMatchingStatus(u64, bool),                  // (put id, matching)
+    PutTaskStatus(Box<(u64, PutTaskStatus)>),            // put id
+    BenchReport(Box<BenchReport>),
+    BenchFinish(Result<(), String>),
+    ShmStatus(Box<ShmStatus>),
+    SessionInfo(Box<SessionInfoData>),
+    SessionTerminated(String), // loop_zenoh 退出的原因
+    ScoutHello(Box<HelloInfo>),
+    ScoutFinish(Result<(), String>),
+    EmbeddedNodeInfo(Box<SessionInfoData>),
+    EmbeddedNodeFinish(Result<(), String>),
 }
 
 pub fn start_async(
     sender_to_gui: Sender<MsgZenohToGui>,
     receiver_from_gui: Receiver<MsgGuiToZenoh>,
     id: u64,
-    config_file_path: PathBuf,
+    config: SessionConfig,
+    overrides: ConfigOverrides,
+    ctx: Context,
 ) {
+    let sender_to_gui = SenderToGui {
+        sender: sender_to_gui,
+        ctx,
+    };
     thread::spawn(move || {
-        let rt = Runtime::new().unwrap();
+        let rt = match Runtime::new() {
+            Ok(o) => o,
+            Err(e) => {
+                let s = format!("create tokio runtime error, {e}");
+                warn!("{s}");
+                let _ = sender_to_gui.send(MsgZenohToGui::OpenSession(Err((id, s))));
+                return;
+            }
+        };
         rt.block_on(loop_zenoh(
             sender_to_gui,
             receiver_from_gui,
-            config_file_path,
+            config,
+            overrides,
             id,
         ));
     });
 }
 
-async fn loop_zenoh(
+// scout 不需要打开 session, 使用单独的线程运行
+pub fn start_scout(
+    sender_to_gui: Sender<MsgZenohToGui>,
+    stop_receiver: Receiver<()>,
+    data: ScoutData,
+    ctx: Context,
+) {
+    let sender_to_gui = SenderToGui {
+        sender: sender_to_gui,
+        ctx,
+    };
+    thread::spawn(move || {
+        let r = match Runtime::new() {
+            Ok(rt) => rt.block_on(task_scout(data, stop_receiver, sender_to_gui.clone())),
+            Err(e) => Err(format!("create tokio runtime error, {e}")),
+        };
+        if let Err(e) = &r {
+            warn!("scout error, {e}");
+        }
+        let _ = sender_to_gui.send(MsgZenohToGui::ScoutFinish(r));
+    });
+}
+
+// 内嵌的 router/peer, 供没有 zenohd 时测试使用
+pub fn start_embedded_node(
     sender_to_gui: Sender<MsgZenohToGui>,
+    stop_receiver: Receiver<()>,
+    data: EmbeddedNodeData,
+    ctx: Context,
+) {
+    let sender_to_gui = SenderToGui {
+        sender: sender_to_gui,
+        ctx,
+    };
+    thread::spawn(move || {
+        let r = match Runtime::new() {
+            Ok(rt) => rt.block_on(task_embedded_node(
+                data,
+                stop_receiver,
+                sender_to_gui.clone(),
+            )),
+            Err(e) => Err(format!("create tokio runtime error, {e}")),
+        };
+        if let Err(e) = &r {
+            warn!("embedded node error, {e}");
+        }
+        let _ = sender_to_gui.send(MsgZenohToGui::EmbeddedNodeFinish(r));
+    });
+}
+
+async fn task_embedded_node(
+    data: EmbeddedNodeData,
+    stop_receiver: Receiver<()>,
+    sender_to_gui: SenderToGui,
+) -> Result<(), String> {
+    info!("task_embedded_node entry");
+    let mut config = Config::default();
+    let mode = serde_json::to_string(&data.mode.to_string()).map_err(|e| e.to_string())?;
+    let listen = serde_json::to_string(&[data.listen]).map_err(|e| e.to_string())?;
+    config
+        .insert_json5("mode", mode.as_str())
+        .map_err(|e| e.to_string())?;
+    config
+        .insert_json5("listen/endpoints", listen.as_str())
+        .map_err(|e| format!("invalid listen endpoint, {e}"))?;
+    // 通过 adminspace 获取连接的 client
+    config
+        .insert_json5("adminspace/enabled", "true")
+        .map_err(|e| e.to_string())?;
+
+    let session = zenoh::open(config).await.map_err(|e| e.to_string())?;
+    let mut refresh_interval = interval(SESSION_INFO_PERIOD);
+    refresh_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    'a: loop {
+        select!(
+            _ = refresh_interval.tick() => {},
+            _ = stop_receiver.recv_async() => {
+                break 'a;
+            },
+        );
+        let info = session_info(&session).await;
+        if sender_to_gui
+            .send(MsgZenohToGui::EmbeddedNodeInfo(Box::new(info)))
+            .is_err()
+        {
+            break 'a;
+        }
+    }
+    let r = session.close().await.map_err(|e| e.to_string());
+    info!("task_embedded_node exit");
+    r
+}
+
+async fn task_scout(
+    data: ScoutData,
+    stop_receiver: Receiver<()>,
+    sender_to_gui: SenderToGui,
+) -> Result<(), String> {
+    info!("task_scout entry");
+    let mut config = Config::default();
+    let address = serde_json::to_string(&data.multicast_address).map_err(|e| e.to_string())?;
+    let interface = serde_json::to_string(&data.multicast_interface).map_err(|e| e.to_string())?;
+    config
+        .insert_json5("scouting/multicast/enabled", "true")
+        .map_err(|e| e.to_string())?;
+    config
+        .insert_json5("scouting/multicast/address", address.as_str())
+        .map_err(|e| format!("invalid multicast address, {e}"))?;
+    config
+        .insert_json5("scouting/multicast/interface", interface.as_str())
+        .map_err(|e| format!("invalid multicast interface, {e}"))?;
+
+    let scout = zenoh::scout(data.what, config)
+        .await
+        .map_err(|e| e.to_string())?;
+    let deadline = sleep(data.duration);
+    tokio::pin!(deadline);
+    'a: loop {
+        select!(
+            hello = scout.recv_async() => {
+                let Ok(hello) = hello else {
+                    break 'a;
+                };
+                let info = HelloInfo {
+                    zid: hello.zid().to_string(),
+                    whatami: hello.whatami().to_string(),
+                    locators: hello.locators().iter().map(|l| l.to_string()).collect(),
+                };
+                if sender_to_gui.send(MsgZenohToGui::ScoutHello(Box::new(info))).is_err() {
+                    break 'a;
+                }
+            },
+
+            _ = &mut deadline => {
+                break 'a;
+            },
+
+            _ = stop_receiver.recv_async() => {
+                break 'a;
+            },
+        );
+    }
+    scout.stop();
+    info!("task_scout exit");
+    Ok(())
+}
+
+// 加载配置后依次应用 overrides
+pub fn build_config(
+    config: SessionConfig,
+    overrides: &[(String, String)],
+) -> Result<Config, String> {
+    let mut config = match config {
+        SessionConfig::File(path) => Config::from_file(path),
+        SessionConfig::Json5(s) => Config::from_json5(s.as_str()),
+    }
+    .map_err(|e| e.to_string())?;
+    for (key, value) in overrides {
+        config
+            .insert_json5(key.as_str(), value.as_str())
+            .map_err(|e| format!("override \"{key}\" error, {e}"))?;
+    }
+    Ok(config)
+}
+
+async fn loop_zenoh(
+    sender_to_gui: SenderToGui,
     receiver_from_gui: Receiver<MsgGuiToZenoh>,
-    config_file_path: PathBuf,
+    config: SessionConfig,
+    overrides: ConfigOverrides,
     id: u64,
 ) {
-    let config = match Config::from_file(config_file_path) {
+    let config = match build_config(config, &overrides) {
         Ok(o) => o,
-        Err(e) => {
-            let s = e.to_string();
+        Err(s) => {
             warn!("{s}");
             let _ = sender_to_gui.send(MsgZenohToGui::OpenSession(Err((id, s))));
             return;
@@ -113,54 +645,114 @@ async fn loop_zenoh(
     };
     let _ = sender_to_gui.send(MsgZenohToGui::OpenSession(Ok(id)));
 
+    let (session_info_sender, session_info_receiver): (Sender<()>, Receiver<()>) = unbounded();
+    task::spawn(task_session_info(
+        session.clone(),
+        session_info_receiver,
+        sender_to_gui.clone(),
+    ));
+
     let mut subscriber_senders: BTreeMap<u64, Sender<()>> = BTreeMap::new();
+    let mut queryable_senders: BTreeMap<u64, Sender<()>> = BTreeMap::new();
+    let mut query_senders: BTreeMap<u64, Sender<()>> = BTreeMap::new(); // get id
+    let mut liveliness_tokens: BTreeMap<u64, LivelinessToken> = BTreeMap::new();
+    let mut liveliness_subscriber_sender: Option<Sender<()>> = None;
+    // put id -> (publisher, matching listener close sender)
+    let mut publishers: BTreeMap<u64, (Arc<PutPublisher>, Sender<()>)> = BTreeMap::new();
+    let mut queriers: BTreeMap<u64, (Arc<Querier<'static>>, Sender<()>)> = BTreeMap::new();
+    let mut put_task_senders: BTreeMap<u64, Sender<PutTaskCtrl>> = BTreeMap::new();
+    let mut bench_sender: Option<Sender<()>> = None;
+    let mut shm_provider: Option<ShmProvider<PosixShmProviderBackendBinaryHeap>> = None;
 
-    'a: loop {
-        let try_read = receiver_from_gui.try_recv();
-        let msg = match try_read {
+    let exit_reason = 'a: loop {
+        let msg = match receiver_from_gui.recv_async().await {
             Ok(m) => m,
-            Err(e) => {
-                match e {
-                    TryRecvError::Empty => {
-                        sleep(Duration::from_millis(8)).await;
-                        continue 'a;
-                    }
-                    TryRecvError::Disconnected => {
-                        break 'a;
-                    }
-                };
+            Err(_) => {
+                break 'a "gui channel closed";
             }
         };
         match msg {
             MsgGuiToZenoh::Close => {
-                break 'a;
+                break 'a "closed";
             }
             MsgGuiToZenoh::AddSubReq(req) => {
                 let SubData {
                     id,
                     key_expr,
                     origin,
+                    queue_size,
+                    batch_period,
+                    drop_policy,
+                    advanced,
                 } = *req;
-                let subscriber: Subscriber<FifoChannelHandler<Sample>> = match session
-                    .declare_subscriber(key_expr)
-                    .allowed_origin(origin)
-                    .await
-                {
-                    Ok(o) => o,
-                    Err(e) => {
-                        let _ = sender_to_gui
-                            .send(MsgZenohToGui::AddSubRes(Box::new((id, Err(e.to_string())))));
-                        return;
+                let builder = session.declare_subscriber(key_expr).allowed_origin(origin);
+                let (close_sender, close_receiver): (Sender<()>, Receiver<()>) = unbounded();
+                let queue = SubQueue::new(queue_size, drop_policy);
+                let r = match advanced {
+                    None => builder.await.map(|subscriber| {
+                        task::spawn(task_subscriber(
+                            id,
+                            subscriber,
+                            None,
+                            queue,
+                            batch_period,
+                            close_receiver,
+                            sender_to_gui.clone(),
+                        ));
+                    }),
+                    Some(advanced) => {
+                        let mut builder = builder.advanced();
+                        if let Some(history) = advanced.history {
+                            let mut config = HistoryConfig::default();
+                            if let Some(n) = history.max_samples {
+                                config = config.max_samples(n);
+                            }
+                            if let Some(age) = history.max_age {
+                                config = config.max_age(age);
+                            }
+                            if history.detect_late_publishers {
+                                config = config.detect_late_publishers();
+                            }
+                            builder = builder.history(config);
+                        }
+                        match advanced.recovery {
+                            SubRecovery::Disabled => {}
+                            SubRecovery::Heartbeat => {
+                                builder = builder.recovery(RecoveryConfig::default().heartbeat());
+                            }
+                            SubRecovery::PeriodicQueries(period) => {
+                                builder = builder
+                                    .recovery(RecoveryConfig::default().periodic_queries(period));
+                            }
+                        }
+                        match builder.await {
+                            Ok(subscriber) => {
+                                subscriber
+                                    .sample_miss_listener()
+                                    .await
+                                    .map(|miss_listener| {
+                                        task::spawn(task_subscriber(
+                                            id,
+                                            subscriber,
+                                            Some(miss_listener),
+                                            queue,
+                                            batch_period,
+                                            close_receiver,
+                                            sender_to_gui.clone(),
+                                        ));
+                                    })
+                            }
+                            Err(e) => Err(e),
+                        }
                     }
                 };
-                let (close_sender, close_receiver): (Sender<()>, Receiver<()>) = unbounded();
+                if let Err(e) = r {
+                    warn!("declare subscriber error, {e}");
+                    let _ = sender_to_gui
+                        .send(MsgZenohToGui::AddSubRes(Box::new((id, Err(e.to_string())))));
+                    continue 'a;
+                }
                 let _ = subscriber_senders.insert(id, close_sender);
-                task::spawn(task_subscriber(
-                    id,
-                    subscriber,
-                    close_receiver,
-                    sender_to_gui.clone(),
-                ));
                 let _ = sender_to_gui.send(MsgZenohToGui::AddSubRes(Box::new((id, Ok(())))));
             }
             MsgGuiToZenoh::DelSubReq(id) => {
@@ -170,96 +762,1539 @@ async fn loop_zenoh(
                 let _ = subscriber_senders.remove(&id);
                 let _ = sender_to_gui.send(MsgZenohToGui::DelSubRes(id));
             }
-            MsgGuiToZenoh::GetReq(req) => {
-                task::spawn(task_query(session.clone(), req, sender_to_gui.clone()));
+            MsgGuiToZenoh::GetReq(mut req) => {
+                let id = req.id;
+                // 同一个 get 重新发送时取消之前的 query
+                if let Some(sender) = query_senders.remove(&id) {
+                    let _ = sender.send(());
+                }
+                query_senders.retain(|_, sender| !sender.is_disconnected());
+                if req.shm {
+                    if let Some((encoding, payload)) = req.value.take() {
+                        match shm_payload(&mut shm_provider, &payload, &sender_to_gui) {
+                            Ok(o) => req.value = Some((encoding, o)),
+                            Err(e) => {
+                                let _ =
+                                    sender_to_gui.send(MsgZenohToGui::GetErr(Box::new((id, e))));
+                                continue 'a;
+                            }
+                        }
+                    }
+                }
+                let (close_sender, close_receiver): (Sender<()>, Receiver<()>) = unbounded();
+                let _ = query_senders.insert(id, close_sender);
+                let querier = queriers.get(&id).map(|(q, _)| q.clone());
+                task::spawn(task_query(
+                    session.clone(),
+                    querier,
+                    req,
+                    close_receiver,
+                    sender_to_gui.clone(),
+                ));
+            }
+            MsgGuiToZenoh::CancelGetReq(id) => {
+                if let Some(sender) = query_senders.remove(&id) {
+                    let _ = sender.send(());
+                }
+            }
+            MsgGuiToZenoh::DeclareQuerierReq(req) => {
+                let id = req.id;
+                if let Some((querier, sender)) = queriers.remove(&id) {
+                    let _ = sender.send(());
+                    undeclare_querier(querier).await;
+                }
+                let r = match declare_querier(&session, *req, sender_to_gui.clone()).await {
+                    Ok((querier, sender)) => {
+                        let _ = queriers.insert(id, (Arc::new(querier), sender));
+                        Ok(())
+                    }
+                    Err(e) => {
+                        warn!("declare querier error, {e}");
+                        Err(e)
+                    }
+                };
+                let _ = sender_to_gui.send(MsgZenohToGui::DeclareQuerierRes(Box::new((id, r))));
+            }
+            MsgGuiToZenoh::UndeclareQuerierReq(id) => {
+                if let Some((querier, sender)) = queriers.remove(&id) {
+                    let _ = sender.send(());
+                    undeclare_querier(querier).await;
+                }
+                let _ = sender_to_gui.send(MsgZenohToGui::UndeclareQuerierRes(id));
             }
             MsgGuiToZenoh::PutReq(p) => {
-                let pd = *p;
-                if let Err(e) = session
-                    .put(pd.key.clone(), pd.payload)
-                    .encoding(pd.encoding)
-                    .congestion_control(pd.congestion_control)
-                    .priority(pd.priority)
-                    .await
-                {
-                    let s = format!("put error \"{}\", {}", pd.key, e);
+                let mut pd = *p;
+                if pd.shm && pd.kind == SampleKind::Put {
+                    match shm_payload(&mut shm_provider, &pd.payload, &sender_to_gui) {
+                        Ok(o) => pd.payload = o,
+                        Err(e) => {
+                            let s = format!("shm alloc error \"{}\", {e}", pd.key);
+                            warn!("{s}");
+                            let _ = sender_to_gui
+                                .send(MsgZenohToGui::PutRes(Box::new((pd.id, false, s))));
+                            continue 'a;
+                        }
+                    }
+                }
+                let publisher = publishers.get(&pd.id).map(|(p, _)| p.as_ref());
+                let (op, r) = publish(&session, publisher, &pd).await;
+                if let Err(e) = r {
+                    let s = format!("{op} error \"{}\", {e}", pd.key);
                     warn!("{s}");
                     let _ = sender_to_gui.send(MsgZenohToGui::PutRes(Box::new((pd.id, false, s))));
                 } else {
-                    let s = format!("put ok \"{}\"", pd.key);
+                    let s = format!("{op} ok \"{}\"", pd.key);
                     info!("{s}");
                     let _ = sender_to_gui.send(MsgZenohToGui::PutRes(Box::new((pd.id, true, s))));
                 }
             }
-        }
-    }
-
-    for (sub_id, sender) in subscriber_senders {
-        let _ = sender.send(());
-        let _ = sender_to_gui.send(MsgZenohToGui::DelSubRes(sub_id));
-    }
-
-    info!("session closed");
-}
-
-async fn task_subscriber(
-    id: u64,
-    subscriber: Subscriber<FifoChannelHandler<Sample>>,
-    close_receiver: Receiver<()>,
-    sender_to_gui: Sender<MsgZenohToGui>,
-) {
-    info!("task_subscriber entry");
-    'a: loop {
-        let r: Result<Sample, RecvError> = select!(
-            sample = subscriber.recv_async() =>{
-                 sample.map_err(|_|RecvError::Disconnected)
-            },
-
-            _ = close_receiver.recv_async() =>{
-                 Err(RecvError::Disconnected)
-            },
-        );
-
-        match r {
-            Ok(sample) => {
-                let t = SystemTime::now();
-                let msg = MsgZenohToGui::SubCB(Box::new((id, sample, t)));
-                if let Err(e) = sender_to_gui.send(msg) {
-                    println!("{}", e);
+            MsgGuiToZenoh::AddQueryableReq(req) => {
+                let QueryableData {
+                    id,
+                    key_expr,
+                    complete,
+                    origin,
+                    reply,
+                } = *req;
+                let queryable: Queryable<FifoChannelHandler<Query>> = match session
+                    .declare_queryable(key_expr.clone())
+                    .complete(complete)
+                    .allowed_origin(origin)
+                    .await
+                {
+                    Ok(o) => o,
+                    Err(e) => {
+                        let _ = sender_to_gui.send(MsgZenohToGui::AddQueryableRes(Box::new((
+                            id,
+                            Err(e.to_string()),
+                        ))));
+                        continue 'a;
+                    }
+                };
+                let (close_sender, close_receiver): (Sender<()>, Receiver<()>) = unbounded();
+                let _ = queryable_senders.insert(id, close_sender);
+                task::spawn(task_queryable(
+                    id,
+                    key_expr,
+                    queryable,
+                    reply,
+                    close_receiver,
+                    sender_to_gui.clone(),
+                ));
+                let _ = sender_to_gui.send(MsgZenohToGui::AddQueryableRes(Box::new((id, Ok(())))));
+            }
+            MsgGuiToZenoh::DelQueryableReq(id) => {
+                if let Some(sender) = queryable_senders.get(&id) {
+                    let _ = sender.send(());
                 }
+                let _ = queryable_senders.remove(&id);
+                let _ = sender_to_gui.send(MsgZenohToGui::DelQueryableRes(id));
             }
-            Err(_) => {
-                break 'a;
+            MsgGuiToZenoh::DeclareTokenReq(req) => {
+                let (id, key_expr) = *req;
+                let r = match session.liveliness().declare_token(key_expr).await {
+                    Ok(token) => {
+                        let _ = liveliness_tokens.insert(id, token);
+                        Ok(())
+                    }
+                    Err(e) => Err(e.to_string()),
+                };
+                let _ = sender_to_gui.send(MsgZenohToGui::DeclareTokenRes(Box::new((id, r))));
             }
+            MsgGuiToZenoh::UndeclareTokenReq(id) => {
+                if let Some(token) = liveliness_tokens.remove(&id) {
+                    if let Err(e) = token.undeclare().await {
+                        warn!("undeclare liveliness token error, {e}");
+                    }
+                }
+                let _ = sender_to_gui.send(MsgZenohToGui::UndeclareTokenRes(id));
+            }
+            MsgGuiToZenoh::AddLivelinessSubReq(req) => {
+                let LivelinessSubData { key_expr, history } = *req;
+                if let Some(sender) = liveliness_subscriber_sender.take() {
+                    let _ = sender.send(());
+                }
+                let subscriber: Subscriber<FifoChannelHandler<Sample>> = match session
+                    .liveliness()
+                    .declare_subscriber(key_expr)
+                    .history(history)
+                    .await
+                {
+                    Ok(o) => o,
+                    Err(e) => {
+                        let _ = sender_to_gui
+                            .send(MsgZenohToGui::AddLivelinessSubRes(Err(e.to_string())));
+                        continue 'a;
+                    }
+                };
+                let (close_sender, close_receiver): (Sender<()>, Receiver<()>) = unbounded();
+                liveliness_subscriber_sender = Some(close_sender);
+                task::spawn(task_liveliness_subscriber(
+                    subscriber,
+                    close_receiver,
+                    sender_to_gui.clone(),
+                ));
+                let _ = sender_to_gui.send(MsgZenohToGui::AddLivelinessSubRes(Ok(())));
+            }
+            MsgGuiToZenoh::DelLivelinessSubReq => {
+                if let Some(sender) = liveliness_subscriber_sender.take() {
+                    let _ = sender.send(());
+                }
+                let _ = sender_to_gui.send(MsgZenohToGui::DelLivelinessSubRes);
+            }
+            MsgGuiToZenoh::LivelinessGetReq(req) => {
+                task::spawn(task_liveliness_query(
+                    session.clone(),
+                    req,
+                    sender_to_gui.clone(),
+                ));
+            }
+            MsgGuiToZenoh::DeclarePublisherReq(req) => {
+                let id = req.id;
+                if let Some((publisher, sender)) = publishers.remove(&id) {
+                    let _ = sender.send(());
+                    undeclare_publisher(publisher).await;
+                }
+                let r = declare_publisher(&session, *req, sender_to_gui.clone()).await;
+                let r = match r {
+                    Ok((publisher, sender)) => {
+                        let _ = publishers.insert(id, (Arc::new(publisher), sender));
+                        Ok(())
+                    }
+                    Err(e) => {
+                        warn!("declare publisher error, {e}");
+                        Err(e)
+                    }
+                };
+                let _ = sender_to_gui.send(MsgZenohToGui::DeclarePublisherRes(Box::new((id, r))));
+            }
+            MsgGuiToZenoh::UndeclarePublisherReq(id) => {
+                if let Some(sender) = put_task_senders.remove(&id) {
+                    let _ = sender.send(PutTaskCtrl::Stop);
+                }
+                if let Some((publisher, sender)) = publishers.remove(&id) {
+                    let _ = sender.send(());
+                    undeclare_publisher(publisher).await;
+                }
+                let _ = sender_to_gui.send(MsgZenohToGui::UndeclarePublisherRes(id));
+            }
+            MsgGuiToZenoh::PutTaskReq(mut req) => {
+                let id = req.put.id;
+                // 所有发送共用同一块 shm
+                if req.put.shm && req.put.kind == SampleKind::Put {
+                    match shm_payload(&mut shm_provider, &req.put.payload, &sender_to_gui) {
+                        Ok(o) => req.put.payload = o,
+                        Err(e) => {
+                            let s = format!("shm alloc error \"{}\", {e}", req.put.key);
+                            let _ =
+                                sender_to_gui.send(MsgZenohToGui::PutRes(Box::new((id, false, s))));
+                            continue 'a;
+                        }
+                    }
+                }
+                if let Some(sender) = put_task_senders.remove(&id) {
+                    let _ = sender.send(PutTaskCtrl::Stop);
+                }
+                put_task_senders.retain(|_, sender| !sender.is_disconnected());
+                let publisher = publishers.get(&id).map(|(p, _)| p.clone());
+                let (ctrl_sender, ctrl_receiver): (Sender<PutTaskCtrl>, Receiver<PutTaskCtrl>) =
+                    unbounded();
+                let _ = put_task_senders.insert(id, ctrl_sender);
+                task::spawn(task_put(
+                    session.clone(),
+                    publisher,
+                    req,
+                    ctrl_receiver,
+                    sender_to_gui.clone(),
+                ));
+            }
+            MsgGuiToZenoh::PutTaskCtrlReq(id, ctrl) => {
+                if let Some(sender) = put_task_senders.get(&id) {
+                    let _ = sender.send(ctrl);
+                }
+            }
+            MsgGuiToZenoh::BenchStartReq(req) => {
+                if let Some(sender) = bench_sender.take() {
+                    let _ = sender.send(());
+                }
+                let (close_sender, close_receiver): (Sender<()>, Receiver<()>) = unbounded();
+                bench_sender = Some(close_sender);
+                task::spawn(task_bench(
+                    session.clone(),
+                    req,
+                    close_receiver,
+                    sender_to_gui.clone(),
+                ));
+            }
+            MsgGuiToZenoh::BenchStopReq => {
+                if let Some(sender) = bench_sender.take() {
+                    let _ = sender.send(());
+                }
+            }
+        }
+    };
+
+    let _ = session_info_sender.send(());
+
+    for (_, sender) in query_senders {
+        let _ = sender.send(());
+    }
+
+    if let Some(sender) = bench_sender {
+        let _ = sender.send(());
+    }
+
+    for (_, sender) in put_task_senders {
+        let _ = sender.send(PutTaskCtrl::Stop);
+    }
+
+    for (put_id, (publisher, sender)) in publishers {
+        let _ = sender.send(());
+        undeclare_publisher(publisher).await;
+        let _ = sender_to_gui.send(MsgZenohToGui::UndeclarePublisherRes(put_id));
+    }
+
+    for (get_id, (querier, sender)) in queriers {
+        let _ = sender.send(());
+        undeclare_querier(querier).await;
+        let _ = sender_to_gui.send(MsgZenohToGui::UndeclareQuerierRes(get_id));
+    }
+
+    for (sub_id, sender) in subscriber_senders {
+        let _ = sender.send(());
+        let _ = sender_to_gui.send(MsgZenohToGui::DelSubRes(sub_id));
+    }
+
+    for (queryable_id, sender) in queryable_senders {
+        let _ = sender.send(());
+        let _ = sender_to_gui.send(MsgZenohToGui::DelQueryableRes(queryable_id));
+    }
+
+    for (token_id, token) in liveliness_tokens {
+        let _ = token.undeclare().await;
+        let _ = sender_to_gui.send(MsgZenohToGui::UndeclareTokenRes(token_id));
+    }
+
+    if let Some(sender) = liveliness_subscriber_sender {
+        let _ = sender.send(());
+        let _ = sender_to_gui.send(MsgZenohToGui::DelLivelinessSubRes);
+    }
+
+    info!("session closed, {exit_reason}");
+    let _ = sender_to_gui.send(MsgZenohToGui::SessionTerminated(exit_reason.to_string()));
+}
+
+const SESSION_INFO_PERIOD: Duration = Duration::from_secs(2);
+const ADMIN_SPACE_TIMEOUT: Duration = Duration::from_millis(500);
+
+// 定时刷新 session 信息
+async fn task_session_info(
+    session: Session,
+    close_receiver: Receiver<()>,
+    sender_to_gui: SenderToGui,
+) {
+    info!("task_session_info entry");
+    let mut refresh_interval = interval(SESSION_INFO_PERIOD);
+    refresh_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        select!(
+            _ = refresh_interval.tick() => {},
+            _ = close_receiver.recv_async() => {
+                break;
+            },
+        );
+        let data = session_info(&session).await;
+        if sender_to_gui
+            .send(MsgZenohToGui::SessionInfo(Box::new(data)))
+            .is_err()
+        {
+            break;
+        }
+    }
+    info!("task_session_info exit");
+}
+
+async fn session_info(session: &Session) -> SessionInfoData {
+    let info = session.info();
+    let config = session.config();
+    // 配置文件中未指定 mode 时默认为 peer
+    let mode = config
+        .get_typed::<Option<String>>("mode")
+        .ok()
+        .flatten()
+        .unwrap_or("peer".to_string());
+    let admin_space = config
+        .get_typed::<bool>("adminspace/enabled")
+        .unwrap_or(false);
+    // connect endpoints 可能按 mode 区分, 此时为 object
+    let connect_endpoints = match config.get_typed::<serde_json::Value>("connect/endpoints") {
+        Ok(serde_json::Value::Array(a)) => !a.is_empty(),
+        Ok(serde_json::Value::Object(o)) => o
+            .values()
+            .any(|v| v.as_array().is_some_and(|a| !a.is_empty())),
+        _ => false,
+    };
+    let routers: Vec<String> = info.routers_zid().await.map(|z| z.to_string()).collect();
+    let peers: Vec<String> = info.peers_zid().await.map(|z| z.to_string()).collect();
+    let health = if !routers.is_empty() || !peers.is_empty() {
+        SessionHealth::Connected
+    } else if mode == "client" || connect_endpoints {
+        SessionHealth::Disconnected
+    } else {
+        SessionHealth::Degraded
+    };
+    let mut data = SessionInfoData {
+        zid: info.zid().await.to_string(),
+        mode,
+        health,
+        routers,
+        peers,
+        locators: Vec::new(),
+        transports: None,
+    };
+    if admin_space {
+        // transports 和 links 只能通过 adminspace 获取
+        let key = format!("@/{}/{}", data.zid, data.mode);
+        match admin_space_local_data(session, key).await {
+            Ok((locators, transports)) => {
+                data.locators = locators;
+                data.transports = Some(transports);
+            }
+            Err(e) => {
+                warn!("query adminspace error, {e}");
+            }
+        }
+    }
+    data
+}
+
+async fn admin_space_local_data(
+    session: &Session,
+    key: String,
+) -> Result<(Vec<String>, Vec<TransportInfo>), String> {
+    let replies = session
+        .get(key)
+        .timeout(ADMIN_SPACE_TIMEOUT)
+        .await
+        .map_err(|e| e.to_string())?;
+    let reply = replies.recv_async().await.map_err(|e| e.to_string())?;
+    let sample = reply.into_result().map_err(|e| {
+        e.payload()
+            .try_to_string()
+            .map(|s| s.to_string())
+            .unwrap_or_default()
+    })?;
+    let value: serde_json::Value =
+        serde_json::from_slice(&sample.payload().to_bytes()).map_err(|e| e.to_string())?;
+
+    let as_string = |v: &serde_json::Value| v.as_str().unwrap_or_default().to_string();
+    let locators = value["locators"]
+        .as_array()
+        .map(|a| a.iter().map(as_string).collect())
+        .unwrap_or_default();
+    let transports = value["sessions"]
+        .as_array()
+        .map(|a| {
+            a.iter()
+                .map(|t| TransportInfo {
+                    zid: as_string(&t["peer"]),
+                    whatami: as_string(&t["whatami"]),
+                    links: t["links"]
+                        .as_array()
+                        .map(|l| {
+                            l.iter()
+                                .map(|l| (as_string(&l["src"]), as_string(&l["dst"])))
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok((locators, transports))
+}
+
+const SHM_POOL_SIZE: usize = 16 * 1024 * 1024;
+
+// 将 payload 复制到 shm 中, provider 在第一次使用时创建
+fn shm_payload(
+    provider: &mut Option<ShmProvider<PosixShmProviderBackendBinaryHeap>>,
+    payload: &ZBytes,
+    sender_to_gui: &SenderToGui,
+) -> Result<ZBytes, String> {
+    // 空 payload 不需要 shm, 也不依赖 provider 对 0 长度分配的处理
+    if payload.is_empty() {
+        return Ok(payload.clone());
+    }
+    let mut status = ShmStatus {
+        pool_size: SHM_POOL_SIZE,
+        available: 0,
+        error: None,
+    };
+    if provider.is_none() {
+        // binary heap backend 可以统计剩余空间
+        match PosixShmProviderBackendBinaryHeap::builder(SHM_POOL_SIZE).wait() {
+            Ok(o) => *provider = Some(ShmProviderBuilder::backend(o).wait()),
+            Err(e) => {
+                let e = format!("create shm provider error, {e}");
+                status.error = Some(e.clone());
+                let _ = sender_to_gui.send(MsgZenohToGui::ShmStatus(Box::new(status)));
+                return Err(e);
+            }
+        }
+    }
+    let Some(provider) = provider.as_ref() else {
+        return Err("no shm provider".to_string());
+    };
+
+    let r = provider
+        .alloc(payload.len())
+        .with_policy::<GarbageCollect>()
+        .wait()
+        .map(|mut sbuf| {
+            sbuf.copy_from_slice(&payload.to_bytes());
+            ZBytes::from(sbuf)
+        })
+        .map_err(|e| e.to_string());
+    status.available = provider.available();
+    status.error = r.as_ref().err().cloned();
+    let _ = sender_to_gui.send(MsgZenohToGui::ShmStatus(Box::new(status)));
+    r
+}
+
+// 单次发送, 已声明 publisher 的 put 项通过 publisher 发送, qos 选项以声明时为准
+async fn publish(
+    session: &Session,
+    publisher: Option<&PutPublisher>,
+    pd: &PutData,
+) -> (&'static str, zenoh::Result<()>) {
+    let timestamp: Option<Timestamp> = match pd.timestamp {
+        PutTimestamp::None => None,
+        PutTimestamp::Now => Some(session.new_timestamp()),
+        PutTimestamp::Time(t) => Some(Timestamp::new(t, *session.new_timestamp().get_id())),
+    };
+    match (pd.kind, publisher) {
+        (SampleKind::Put, Some(PutPublisher::Plain(publisher))) => (
+            "publisher put",
+            publisher
+                .put(pd.payload.clone())
+                .encoding(pd.encoding.clone())
+                .attachment(pd.attachment.clone())
+                .timestamp(timestamp)
+                .await,
+        ),
+        (SampleKind::Delete, Some(PutPublisher::Plain(publisher))) => (
+            "publisher delete",
+            publisher
+                .delete()
+                .attachment(pd.attachment.clone())
+                .timestamp(timestamp)
+                .await,
+        ),
+        (SampleKind::Put, Some(PutPublisher::Advanced(publisher))) => (
+            "advanced publisher put",
+            publisher
+                .put(pd.payload.clone())
+                .encoding(pd.encoding.clone())
+                .attachment(pd.attachment.clone())
+                .timestamp(timestamp)
+                .await,
+        ),
+        (SampleKind::Delete, Some(PutPublisher::Advanced(publisher))) => (
+            "advanced publisher delete",
+            publisher
+                .delete()
+                .attachment(pd.attachment.clone())
+                .timestamp(timestamp)
+                .await,
+        ),
+        (SampleKind::Put, None) => (
+            "put",
+            session
+                .put(pd.key.clone(), pd.payload.clone())
+                .encoding(pd.encoding.clone())
+                .congestion_control(pd.congestion_control)
+                .priority(pd.priority)
+                .express(pd.express)
+                .reliability(pd.reliability)
+                .allowed_destination(pd.destination)
+                .attachment(pd.attachment.clone())
+                .timestamp(timestamp)
+                .await,
+        ),
+        (SampleKind::Delete, None) => (
+            "delete",
+            session
+                .delete(pd.key.clone())
+                .congestion_control(pd.congestion_control)
+                .priority(pd.priority)
+                .express(pd.express)
+                .reliability(pd.reliability)
+                .allowed_destination(pd.destination)
+                .attachment(pd.attachment.clone())
+                .timestamp(timestamp)
+                .await,
+        ),
+    }
+}
+
+// 若仍有 put task 持有该 publisher, 则在其退出时随 drop 一起撤销
+async fn undeclare_publisher(publisher: Arc<PutPublisher>) {
+    let r = match Arc::try_unwrap(publisher) {
+        Ok(PutPublisher::Plain(publisher)) => publisher.undeclare().await,
+        Ok(PutPublisher::Advanced(publisher)) => publisher.undeclare().await,
+        Err(_) => Ok(()),
+    };
+    if let Err(e) = r {
+        warn!("undeclare publisher error, {e}");
+    }
+}
+
+const PUT_TASK_RATE_MIN: f64 = 0.001; // Hz
+const PUT_TASK_RATE_MAX: f64 = 1000000.0; // Hz
+const PUT_TASK_BURST_MAX: u64 = 100000;
+
+async fn task_put(
+    session: Session,
+    publisher: Option<Arc<PutPublisher>>,
+    data: Box<PutTaskData>,
+    ctrl_receiver: Receiver<PutTaskCtrl>,
+    sender_to_gui: SenderToGui,
+) {
+    let PutTaskData {
+        put,
+        rate,
+        stop,
+        burst,
+    } = *data;
+    let id = put.id;
+    info!("task_put entry, key \"{}\"", put.key);
+
+    // 归档中的设置没有经过界面的范围限制, 这里再检查一次, 避免 interval 的周期为 0 时 panic
+    let burst = burst.clamp(1, PUT_TASK_BURST_MAX);
+    let rate = if rate.is_finite() {
+        rate.clamp(PUT_TASK_RATE_MIN, PUT_TASK_RATE_MAX)
+    } else {
+        PUT_TASK_RATE_MIN
+    };
+    let period = Duration::from_secs_f64(burst as f64 / rate).max(Duration::from_micros(1));
+    let mut send_interval = interval(period);
+    // 周期小于定时器精度时, 由错过的 tick 补发, 保证实际速率
+    send_interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
+    let mut report_interval = interval(Duration::from_millis(200));
+
+    let mut status = PutTaskStatus {
+        sent: 0,
+        failed: 0,
+        rate: 0.0,
+        state: PutTaskState::Running,
+        last_error: None,
+    };
+    let mut active_time = Duration::ZERO; // 不含暂停的运行时长
+    let mut resume_instant = Instant::now();
+    let mut report_instant = Instant::now();
+    let mut report_count: u64 = 0;
+
+    'a: loop {
+        let finished = match stop {
+            PutTaskStop::Count(n) => status.sent + status.failed >= n,
+            PutTaskStop::Duration(d) => {
+                status.state == PutTaskState::Running && active_time + resume_instant.elapsed() >= d
+            }
+        };
+        if finished {
+            break 'a;
+        }
+
+        let running = status.state == PutTaskState::Running;
+        select!(
+            ctrl = ctrl_receiver.recv_async() => {
+                match ctrl {
+                    Ok(PutTaskCtrl::Pause) if running => {
+                        active_time += resume_instant.elapsed();
+                        status.state = PutTaskState::Paused;
+                    }
+                    Ok(PutTaskCtrl::Resume) if !running => {
+                        resume_instant = Instant::now();
+                        send_interval.reset();
+                        status.state = PutTaskState::Running;
+                    }
+                    Ok(PutTaskCtrl::Pause) | Ok(PutTaskCtrl::Resume) => {}
+                    Ok(PutTaskCtrl::Stop) | Err(_) => {
+                        break 'a;
+                    }
+                }
+            },
+
+            _ = send_interval.tick(), if running => {
+                let n = match stop {
+                    PutTaskStop::Count(n) => burst.min(n - status.sent - status.failed),
+                    PutTaskStop::Duration(_) => burst,
+                };
+                for _ in 0..n {
+                    let (_, r) = publish(&session, publisher.as_deref(), &put).await;
+                    match r {
+                        Ok(_) => status.sent += 1,
+                        Err(e) => {
+                            status.failed += 1;
+                            status.last_error = Some(e.to_string());
+                        }
+                    }
+                }
+            },
+
+            _ = report_interval.tick() => {
+                let total = status.sent + status.failed;
+                let elapsed = report_instant.elapsed().as_secs_f64();
+                status.rate = if running && elapsed > 0.0 {
+                    (total - report_count) as f64 / elapsed
+                } else {
+                    0.0
+                };
+                report_instant = Instant::now();
+                report_count = total;
+                let msg = MsgZenohToGui::PutTaskStatus(Box::new((id, status.clone())));
+                if sender_to_gui.send(msg).is_err() {
+                    break 'a;
+                }
+            },
+        );
+    }
+
+    status.state = PutTaskState::Finished;
+    status.rate = 0.0;
+    let _ = sender_to_gui.send(MsgZenohToGui::PutTaskStatus(Box::new((id, status))));
+    info!("task_put exit, key \"{}\"", put.key);
+}
+
+const BENCH_REPORT_PERIOD: Duration = Duration::from_millis(500);
+const BENCH_PONG_TIMEOUT: Duration = Duration::from_secs(1);
+const BENCH_THR_YIELD_PUTS: u64 = 100; // 连续发送多少次后让出线程
+
+async fn task_bench(
+    session: Session,
+    data: Box<BenchData>,
+    close_receiver: Receiver<()>,
+    sender_to_gui: SenderToGui,
+) {
+    info!("task_bench entry");
+    let r = match data.mode {
+        BenchMode::Ping => bench_ping(&session, &data, &close_receiver, &sender_to_gui).await,
+        BenchMode::Pong => bench_pong(&session, &data, &close_receiver, &sender_to_gui).await,
+        BenchMode::ThrPub => bench_thr_pub(&session, &data, &close_receiver, &sender_to_gui).await,
+        BenchMode::ThrSub => bench_thr_sub(&session, &data, &close_receiver, &sender_to_gui).await,
+    };
+    if let Err(e) = &r {
+        warn!("bench error, {e}");
+    }
+    let _ = sender_to_gui.send(MsgZenohToGui::BenchFinish(r));
+    info!("task_bench exit");
+}
+
+async fn declare_bench_publisher(
+    session: &Session,
+    key_expr: OwnedKeyExpr,
+    data: &BenchData,
+) -> Result<Publisher<'static>, String> {
+    session
+        .declare_publisher(key_expr)
+        .congestion_control(data.congestion_control)
+        .priority(data.priority)
+        .express(data.express)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn bench_ping(
+    session: &Session,
+    data: &BenchData,
+    close_receiver: &Receiver<()>,
+    sender_to_gui: &SenderToGui,
+) -> Result<(), String> {
+    let publisher = declare_bench_publisher(session, data.ping_key.clone(), data).await?;
+    let subscriber: Subscriber<FifoChannelHandler<Sample>> = session
+        .declare_subscriber(data.pong_key.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+    let payload = ZBytes::from(vec![0u8; data.payload_size]);
+
+    let mut rtts: Vec<Duration> = Vec::new();
+    let mut lost: u64 = 0;
+    let mut report_instant = Instant::now();
+    'a: for _ in 0..data.ping_count {
+        // 丢弃超时后才到达的回复
+        while let Ok(Some(_)) = subscriber.try_recv() {}
+
+        let t = Instant::now();
+        publisher
+            .put(payload.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        select!(
+            r = subscriber.recv_async() => {
+                r.map_err(|e| e.to_string())?;
+                rtts.push(t.elapsed());
+            },
+
+            _ = sleep(BENCH_PONG_TIMEOUT) => {
+                lost += 1;
+            },
+
+            _ = close_receiver.recv_async() => {
+                break 'a;
+            },
+        );
+
+        if report_instant.elapsed() >= BENCH_REPORT_PERIOD {
+            let msg = MsgZenohToGui::BenchReport(Box::new(BenchReport::Rtt(
+                mem::take(&mut rtts),
+                mem::take(&mut lost),
+            )));
+            let _ = sender_to_gui.send(msg);
+            report_instant = Instant::now();
+        }
+
+        if !data.ping_interval.is_zero() {
+            select!(
+                _ = sleep(data.ping_interval) => {},
+
+                _ = close_receiver.recv_async() => {
+                    break 'a;
+                },
+            );
+        }
+    }
+
+    let msg = MsgZenohToGui::BenchReport(Box::new(BenchReport::Rtt(rtts, lost)));
+    let _ = sender_to_gui.send(msg);
+    Ok(())
+}
+
+async fn bench_pong(
+    session: &Session,
+    data: &BenchData,
+    close_receiver: &Receiver<()>,
+    sender_to_gui: &SenderToGui,
+) -> Result<(), String> {
+    let publisher = declare_bench_publisher(session, data.pong_key.clone(), data).await?;
+    let subscriber: Subscriber<FifoChannelHandler<Sample>> = session
+        .declare_subscriber(data.ping_key.clone())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut report_interval = interval(BENCH_REPORT_PERIOD);
+    report_interval.tick().await; // 第一次 tick 立即完成
+    let mut report_instant = Instant::now();
+    let mut msgs: u64 = 0;
+    let mut bytes: u64 = 0;
+    'a: loop {
+        select!(
+            r = subscriber.recv_async() => {
+                let sample = r.map_err(|e| e.to_string())?;
+                let payload = sample.payload().clone();
+                bytes += payload.len() as u64;
+                msgs += 1;
+                publisher.put(payload).await.map_err(|e| e.to_string())?;
+            },
+
+            _ = report_interval.tick() => {
+                let msg = MsgZenohToGui::BenchReport(Box::new(BenchReport::Rate(
+                    report_instant.elapsed(),
+                    mem::take(&mut msgs),
+                    mem::take(&mut bytes),
+                )));
+                let _ = sender_to_gui.send(msg);
+                report_instant = Instant::now();
+            },
+
+            _ = close_receiver.recv_async() => {
+                break 'a;
+            },
+        );
+    }
+    Ok(())
+}
+
+async fn bench_thr_pub(
+    session: &Session,
+    data: &BenchData,
+    close_receiver: &Receiver<()>,
+    sender_to_gui: &SenderToGui,
+) -> Result<(), String> {
+    let publisher = declare_bench_publisher(session, data.thr_key.clone(), data).await?;
+    let payload = ZBytes::from(vec![0u8; data.payload_size]);
+
+    let mut report_instant = Instant::now();
+    let mut msgs: u64 = 0;
+    while close_receiver.is_empty() && !close_receiver.is_disconnected() {
+        publisher
+            .put(payload.clone())
+            .await
+            .map_err(|e| e.to_string())?;
+        msgs += 1;
+        // 避免一直占用 runtime, 其他任务 (包括停止命令) 得不到执行
+        if msgs % BENCH_THR_YIELD_PUTS == 0 {
+            task::yield_now().await;
+        }
+
+        if report_instant.elapsed() >= BENCH_REPORT_PERIOD {
+            let msg = MsgZenohToGui::BenchReport(Box::new(BenchReport::Rate(
+                report_instant.elapsed(),
+                msgs,
+                msgs * data.payload_size as u64,
+            )));
+            let _ = sender_to_gui.send(msg);
+            report_instant = Instant::now();
+            msgs = 0;
         }
     }
+    Ok(())
+}
+
+async fn bench_thr_sub(
+    session: &Session,
+    data: &BenchData,
+    close_receiver: &Receiver<()>,
+    sender_to_gui: &SenderToGui,
+) -> Result<(), String> {
+    let msgs = Arc::new(AtomicU64::new(0));
+    let bytes = Arc::new(AtomicU64::new(0));
+    let (cb_msgs, cb_bytes) = (msgs.clone(), bytes.clone());
+    // 使用回调统计, 避免 channel 成为瓶颈
+    let subscriber = session
+        .declare_subscriber(data.thr_key.clone())
+        .callback(move |sample: Sample| {
+            cb_msgs.fetch_add(1, Ordering::Relaxed);
+            cb_bytes.fetch_add(sample.payload().len() as u64, Ordering::Relaxed);
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut report_interval = interval(BENCH_REPORT_PERIOD);
+    report_interval.tick().await; // 第一次 tick 立即完成
+    let mut report_instant = Instant::now();
+    'a: loop {
+        select!(
+            _ = report_interval.tick() => {
+                let msg = MsgZenohToGui::BenchReport(Box::new(BenchReport::Rate(
+                    report_instant.elapsed(),
+                    msgs.swap(0, Ordering::Relaxed),
+                    bytes.swap(0, Ordering::Relaxed),
+                )));
+                let _ = sender_to_gui.send(msg);
+                report_instant = Instant::now();
+            },
+
+            _ = close_receiver.recv_async() => {
+                break 'a;
+            },
+        );
+    }
+    subscriber.undeclare().await.map_err(|e| e.to_string())
+}
+
+async fn declare_publisher(
+    session: &Session,
+    data: PublisherData,
+    sender_to_gui: SenderToGui,
+) -> Result<(PutPublisher, Sender<()>), String> {
+    let PublisherData {
+        id,
+        key,
+        congestion_control,
+        priority,
+        express,
+        reliability,
+        destination,
+        advanced,
+    } = data;
+    let builder = session
+        .declare_publisher(key)
+        .congestion_control(congestion_control)
+        .priority(priority)
+        .express(express)
+        .reliability(reliability)
+        .allowed_destination(destination);
+    let (publisher, matching) = match advanced {
+        None => {
+            let publisher = builder.await.map_err(|e| e.to_string())?;
+            let matching = publisher.matching_status().await;
+            (PutPublisher::Plain(publisher), matching)
+        }
+        Some(advanced) => {
+            let mut builder = builder.advanced();
+            if let Some(n) = advanced.cache {
+                builder = builder.cache(CacheConfig::default().max_samples(n));
+            }
+            let miss_detection = match advanced.miss_detection {
+                PubMissDetection::Disabled => None,
+                PubMissDetection::SequenceNumber => Some(MissDetectionConfig::default()),
+                PubMissDetection::Heartbeat(period) => {
+                    Some(MissDetectionConfig::default().heartbeat(period))
+                }
+                PubMissDetection::SporadicHeartbeat(period) => {
+                    Some(MissDetectionConfig::default().sporadic_heartbeat(period))
+                }
+            };
+            if let Some(config) = miss_detection {
+                builder = builder.sample_miss_detection(config);
+            }
+            if advanced.publisher_detection {
+                builder = builder.publisher_detection();
+            }
+            let publisher = builder.await.map_err(|e| e.to_string())?;
+            let matching = publisher.matching_status().await;
+            (PutPublisher::Advanced(publisher), matching)
+        }
+    };
+    let matching = matching.map_err(|e| e.to_string())?.matching();
+    let _ = sender_to_gui.send(MsgZenohToGui::PublisherMatchingStatus(id, matching));
+    let listener: MatchingListener<FifoChannelHandler<MatchingStatus>> = match &publisher {
+        PutPublisher::Plain(publisher) => publisher.matching_listener().await,
+        PutPublisher::Advanced(publisher) => publisher.matching_listener().await,
+    }
+    .map_err(|e| e.to_string())?;
+    let (close_sender, close_receiver): (Sender<()>, Receiver<()>) = unbounded();
+    task::spawn(task_matching_listener(
+        id,
+        listener,
+        MsgZenohToGui::PublisherMatchingStatus,
+        close_receiver,
+        sender_to_gui,
+    ));
+    Ok((publisher, close_sender))
+}
+
+async fn declare_querier(
+    session: &Session,
+    data: QuerierData,
+    sender_to_gui: SenderToGui,
+) -> Result<(Querier<'static>, Sender<()>), String> {
+    let QuerierData {
+        id,
+        key_expr,
+        target,
+        consolidation,
+        locality,
+        timeout,
+        accept_replies,
+    } = data;
+    let querier: Querier<'static> = session
+        .declare_querier(key_expr)
+        .target(target)
+        .consolidation(consolidation)
+        .allowed_destination(locality)
+        .timeout(timeout)
+        .accept_replies(accept_replies)
+        .await
+        .map_err(|e| e.to_string())?;
+    let matching = querier
+        .matching_status()
+        .await
+        .map_err(|e| e.to_string())?
+        .matching();
+    let _ = sender_to_gui.send(MsgZenohToGui::QuerierMatchingStatus(id, matching));
+    let listener: MatchingListener<FifoChannelHandler<MatchingStatus>> = querier
+        .matching_listener()
+        .await
+        .map_err(|e| e.to_string())?;
+    let (close_sender, close_receiver): (Sender<()>, Receiver<()>) = unbounded();
+    task::spawn(task_matching_listener(
+        id,
+        listener,
+        MsgZenohToGui::QuerierMatchingStatus,
+        close_receiver,
+        sender_to_gui,
+    ));
+    Ok((querier, close_sender))
+}
+
+// 若仍有 query 持有该 querier, 则在其结束时随 drop 一起撤销
+async fn undeclare_querier(querier: Arc<Querier<'static>>) {
+    if let Ok(querier) = Arc::try_unwrap(querier) {
+        if let Err(e) = querier.undeclare().await {
+            warn!("undeclare querier error, {e}");
+        }
+    }
+}
+
+// publisher 和 querier 共用, to_msg 生成对应的 matching status 消息
+async fn task_matching_listener(
+    id: u64,
+    listener: MatchingListener<FifoChannelHandler<MatchingStatus>>,
+    to_msg: fn(u64, bool) -> MsgZenohToGui,
+    close_receiver: Receiver<()>,
+    sender_to_gui: SenderToGui,
+) {
+    info!("task_matching_listener entry");
+    'a: loop {
+        let r: Result<MatchingStatus, RecvError> = select!(
+            status = listener.recv_async() =>{
+                 status.map_err(|_|RecvError::Disconnected)
+            },
+
+            _ = close_receiver.recv_async() =>{
+                 Err(RecvError::Disconnected)
+            },
+        );
+
+        match r {
+            Ok(status) => {
+                let msg = to_msg(id, status.matching());
+                if let Err(e) = sender_to_gui.send(msg) {
+                    error!("{e}");
+                }
+            }
+            Err(_) => {
+                break 'a;
+            }
+        }
+    }
+    info!("task_matching_listener exit");
+}
+
+// 在发送到 gui 之前缓存 sample, 数量超过上限时按策略丢弃
+struct SubQueue {
+    size: usize,
+    policy: SubDropPolicy,
+    queue: VecDeque<(Sample, SystemTime)>,
+    latest: BTreeMap<String, (Sample, SystemTime)>, // KeepLatest 使用
+    received: u64,
+    dropped: u64,
+}
+
+impl SubQueue {
+    fn new(size: usize, policy: SubDropPolicy) -> Self {
+        SubQueue {
+            size: size.max(1),
+            policy,
+            queue: VecDeque::new(),
+            latest: BTreeMap::new(),
+            received: 0,
+            dropped: 0,
+        }
+    }
+
+    fn push(&mut self, sample: Sample, t: SystemTime) {
+        self.received += 1;
+        match self.policy {
+            SubDropPolicy::KeepLatest => {
+                let key = sample.key_expr().to_string();
+                // 通配订阅时新的 key 不能一直被丢弃, 替换最久没有更新的 key
+                if !self.latest.contains_key(&key) && self.latest.len() >= self.size {
+                    let oldest = self
+                        .latest
+                        .iter()
+                        .min_by_key(|(_, (_, t))| *t)
+                        .map(|(k, _)| k.clone());
+                    if let Some(k) = oldest {
+                        let _ = self.latest.remove(&k);
+                        self.dropped += 1;
+                    }
+                }
+                if self.latest.insert(key, (sample, t)).is_some() {
+                    self.dropped += 1;
+                }
+                return;
+            }
+            SubDropPolicy::SampleEvery(n) => {
+                if (self.received - 1) % n.max(1) != 0 {
+                    self.dropped += 1;
+                    return;
+                }
+            }
+            SubDropPolicy::DropOldest => {}
+        }
+        if self.queue.len() >= self.size {
+            let _ = self.queue.pop_front();
+            self.dropped += 1;
+        }
+        self.queue.push_back((sample, t));
+    }
+
+    fn take(&mut self) -> SampleBatch {
+        match self.policy {
+            SubDropPolicy::KeepLatest => {
+                let mut v: SampleBatch = mem::take(&mut self.latest).into_values().collect();
+                v.sort_by_key(|(_, t)| *t);
+                v
+            }
+            _ => self.queue.drain(..).collect(),
+        }
+    }
+}
+
+// subscriber 可以是普通的 Subscriber 或者 AdvancedSubscriber
+async fn task_subscriber<S>(
+    id: u64,
+    subscriber: S,
+    mut miss_listener: Option<SampleMissListener<FifoChannelHandler<Miss>>>,
+    mut queue: SubQueue,
+    batch_period: Duration,
+    close_receiver: Receiver<()>,
+    sender_to_gui: SenderToGui,
+) where
+    S: Deref<Target = FifoChannelHandler<Sample>>,
+{
+    info!("task_subscriber entry");
+    let mut batch_interval = interval(batch_period.max(Duration::from_millis(1)));
+    batch_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut reported_dropped: u64 = 0;
+    'a: loop {
+        select!(
+            sample = subscriber.recv_async() => {
+                match sample {
+                    Ok(sample) => queue.push(sample, SystemTime::now()),
+                    Err(_) => {
+                        break 'a;
+                    }
+                }
+            },
+
+            miss = recv_miss(&miss_listener), if miss_listener.is_some() => {
+                let Some(miss) = miss else {
+                    miss_listener = None;
+                    continue 'a;
+                };
+                let source = miss.source();
+                let sample_miss = SampleMiss {
+                    source_zid: source.zid().to_string(),
+                    source_eid: source.eid(),
+                    missed: miss.nb(),
+                };
+                warn!(
+                    "sub {id} missed {} samples from {}:{}",
+                    sample_miss.missed, sample_miss.source_zid, sample_miss.source_eid
+                );
+                let _ = sender_to_gui.send(MsgZenohToGui::SubMiss(Box::new((id, sample_miss))));
+            },
+
+            _ = batch_interval.tick() => {
+                let samples = queue.take();
+                if samples.is_empty() && queue.dropped == reported_dropped {
+                    continue 'a;
+                }
+                reported_dropped = queue.dropped;
+                let msg = MsgZenohToGui::SubCB(Box::new((id, samples, queue.dropped)));
+                if let Err(e) = sender_to_gui.send(msg) {
+                    // gui 已经关闭, 无法再报告错误
+                    warn!("sub {id} send samples to gui error, {e}");
+                    break 'a;
+                }
+            },
+
+            _ = close_receiver.recv_async() => {
+                break 'a;
+            },
+        );
+    }
     info!("task_subscriber exit");
 }
 
-async fn task_query(session: Session, data: Box<QueryData>, sender_to_gui: Sender<MsgZenohToGui>) {
+async fn recv_miss(
+    miss_listener: &Option<SampleMissListener<FifoChannelHandler<Miss>>>,
+) -> Option<Miss> {
+    match miss_listener {
+        Some(l) => l.recv_async().await.ok(),
+        None => None,
+    }
+}
+
+async fn task_liveliness_subscriber(
+    subscriber: Subscriber<FifoChannelHandler<Sample>>,
+    close_receiver: Receiver<()>,
+    sender_to_gui: SenderToGui,
+) {
+    info!("task_liveliness_subscriber entry");
+    'a: loop {
+        let r: Result<Sample, RecvError> = select!(
+            sample = subscriber.recv_async() =>{
+                 sample.map_err(|_|RecvError::Disconnected)
+            },
+
+            _ = close_receiver.recv_async() =>{
+                 Err(RecvError::Disconnected)
+            },
+        );
+
+        match r {
+            Ok(sample) => {
+                let msg = MsgZenohToGui::LivelinessSubCB(Box::new((sample, SystemTime::now())));
+                if let Err(e) = sender_to_gui.send(msg) {
+                    error!("{e}");
+                }
+            }
+            Err(_) => {
+                break 'a;
+            }
+        }
+    }
+    info!("task_liveliness_subscriber exit");
+}
+
+async fn task_liveliness_query(
+    session: Session,
+    data: Box<LivelinessGetData>,
+    sender_to_gui: SenderToGui,
+) {
+    let LivelinessGetData { key_expr, timeout } = *data;
+    info!("task_liveliness_query entry, key expr \"{key_expr}\"");
+
+    let r = match session
+        .liveliness()
+        .get(key_expr.clone())
+        .timeout(timeout)
+        .await
+    {
+        Ok(replies) => {
+            while let Ok(reply) = replies.recv_async().await {
+                let msg = MsgZenohToGui::LivelinessGetRes(Box::new((reply, SystemTime::now())));
+                if let Err(e) = sender_to_gui.send(msg) {
+                    error!("{e}");
+                }
+            }
+            Ok(())
+        }
+        Err(e) => {
+            warn!("liveliness get error \"{key_expr}\", {e}");
+            Err(e.to_string())
+        }
+    };
+    let _ = sender_to_gui.send(MsgZenohToGui::LivelinessGetFinish(r));
+
+    info!("task_liveliness_query exit, key expr \"{key_expr}\"");
+}
+
+async fn task_queryable(
+    id: u64,
+    key_expr: OwnedKeyExpr,
+    queryable: Queryable<FifoChannelHandler<Query>>,
+    reply: QueryReply,
+    close_receiver: Receiver<()>,
+    sender_to_gui: SenderToGui,
+) {
+    info!("task_queryable entry, key expr \"{key_expr}\"");
+    'a: loop {
+        let r: Result<Query, RecvError> = select!(
+            query = queryable.recv_async() =>{
+                 query.map_err(|_|RecvError::Disconnected)
+            },
+
+            _ = close_receiver.recv_async() =>{
+                 Err(RecvError::Disconnected)
+            },
+        );
+
+        let query = match r {
+            Ok(o) => o,
+            Err(_) => {
+                break 'a;
+            }
+        };
+
+        // 若 queryable 的 key expr 含有通配符, 则使用 query 的 key expr 进行应答
+        let reply_key = if key_expr.is_wild() {
+            query.key_expr().clone().into_owned()
+        } else {
+            key_expr.clone().into()
+        };
+        let reply_result = match &reply {
+            QueryReply::Ok(encoding, payload) => query
+                .reply(reply_key, payload.clone())
+                .encoding(encoding.clone())
+                .await
+                .map_err(|e| e.to_string()),
+            QueryReply::Err(encoding, payload) => query
+                .reply_err(payload.clone())
+                .encoding(encoding.clone())
+                .await
+                .map_err(|e| e.to_string()),
+            QueryReply::Ignore => Ok(()),
+        };
+        if let Err(e) = &reply_result {
+            warn!("reply error \"{}\", {}", query.key_expr(), e);
+        }
+
+        let record = QueryRecord {
+            key_expr: query.key_expr().to_string(),
+            parameters: query.parameters().to_string(),
+            attachment: query.attachment().cloned(),
+            value: query
+                .payload()
+                .map(|p| (query.encoding().cloned().unwrap_or_default(), p.clone())),
+            reply_result,
+        };
+        let msg = MsgZenohToGui::QueryableCB(Box::new((id, record, SystemTime::now())));
+        if let Err(e) = sender_to_gui.send(msg) {
+            error!("{e}");
+        }
+    }
+    info!("task_queryable exit, key expr \"{key_expr}\"");
+}
+
+// 已声明 querier 的 get 项通过 querier 发送, key expr 和选项以声明时为准
+async fn task_query(
+    session: Session,
+    querier: Option<Arc<Querier<'static>>>,
+    data: Box<QueryData>,
+    close_receiver: Receiver<()>,
+    sender_to_gui: SenderToGui,
+) {
     let d = *data;
     let key_expr_str = d.key_expr.to_string();
-    info!("task_query entry, key expr \"{}\"", key_expr_str);
-
-    let selector = Selector::from((d.key_expr, d.parameters));
-    let replies = match d.value {
-        Some((encoding, payload)) => session.get(selector).payload(payload).encoding(encoding),
-        None => session.get(selector),
-    }
-    .attachment(d.attachment)
-    .target(d.target)
-    .timeout(d.timeout)
-    .consolidation(d.consolidation)
-    .allowed_destination(d.locality)
-    .await
-    .unwrap();
-
-    while let Ok(reply) = replies.recv_async().await {
+    info!("task_query entry, key expr \"{key_expr_str}\"");
+    let start = Instant::now();
+
+    let replies = match querier {
+        Some(querier) => {
+            match d.value {
+                Some((encoding, payload)) => querier
+                    .get()
+                    .parameters(d.parameters)
+                    .payload(payload)
+                    .encoding(encoding),
+                None => querier.get().parameters(d.parameters),
+            }
+            .attachment(d.attachment)
+            .await
+        }
+        None => {
+            let selector = Selector::from((d.key_expr, d.parameters));
+            match d.value {
+                Some((encoding, payload)) => {
+                    session.get(selector).payload(payload).encoding(encoding)
+                }
+                None => session.get(selector),
+            }
+            .attachment(d.attachment)
+            .target(d.target)
+            .timeout(d.timeout)
+            .consolidation(d.consolidation)
+            .allowed_destination(d.locality)
+            .accept_replies(d.accept_replies)
+            .await
+        }
+    };
+    let replies = match replies {
+        Ok(o) => o,
+        Err(e) => {
+            warn!("get error \"{key_expr_str}\", {e}");
+            let _ = sender_to_gui.send(MsgZenohToGui::GetErr(Box::new((d.id, e.to_string()))));
+            return;
+        }
+    };
+
+    let mut finish = QueryFinish {
+        replies: 0,
+        elapsed: Duration::ZERO,
+        reason: QueryEndReason::Completed,
+    };
+    'a: loop {
+        let reply = select!(
+            reply = replies.recv_async() => {
+                match reply {
+                    Ok(o) => o,
+                    Err(_) => {
+                        break 'a;
+                    }
+                }
+            },
+
+            _ = close_receiver.recv_async() => {
+                // zenoh 没有取消 query 的接口, 丢弃 replies 后不再接收应答
+                finish.reason = QueryEndReason::Cancelled;
+                break 'a;
+            },
+        );
+
+        // 超时的时候 zenoh 会发送一个 "Timeout" 错误应答, 然后结束 query
+        match reply.result() {
+            Err(e) if is_timeout_reply(e) => finish.reason = QueryEndReason::Timeout,
+            _ => finish.replies += 1,
+        }
         let msg = MsgZenohToGui::GetRes(Box::new((d.id, reply)));
         if let Err(e) = sender_to_gui.send(msg) {
-            error!("{}", e);
+            error!("{e}");
         }
     }
 
-    info!("task_query exit, key expr {}", key_expr_str);
+    finish.elapsed = start.elapsed();
+    let _ = sender_to_gui.send(MsgZenohToGui::GetFinish(Box::new((d.id, finish))));
+    info!("task_query exit, key expr {key_expr_str}");
+}
+
+fn is_timeout_reply(e: &ReplyError) -> bool {
+    *e.encoding() == Encoding::ZENOH_STRING
+        && e.payload().try_to_string().is_ok_and(|s| s == "Timeout")
+}
+
+#[test]
+fn sub_queue_drop_oldest() {
+    use std::time::UNIX_EPOCH;
+    use zenoh::sample::SampleBuilder;
+
+    let mut queue = SubQueue::new(3, SubDropPolicy::DropOldest);
+    for i in 0..5 {
+        let sample = SampleBuilder::put(OwnedKeyExpr::new("demo/a").unwrap(), i.to_string()).into();
+        queue.push(sample, UNIX_EPOCH + Duration::from_secs(i));
+    }
+    assert_eq!(queue.received, 5);
+    assert_eq!(queue.dropped, 2);
+    let payloads: Vec<String> = queue
+        .take()
+        .iter()
+        .map(|(s, _)| s.payload().try_to_string().unwrap().to_string())
+        .collect();
+    assert_eq!(payloads, vec!["2", "3", "4"]);
+    assert!(queue.take().is_empty());
+}
+
+#[test]
+fn sub_queue_sample_every() {
+    use std::time::UNIX_EPOCH;
+    use zenoh::sample::SampleBuilder;
+
+    let mut queue = SubQueue::new(100, SubDropPolicy::SampleEvery(3));
+    for i in 0..10 {
+        let sample = SampleBuilder::put(OwnedKeyExpr::new("demo/a").unwrap(), i.to_string()).into();
+        queue.push(sample, UNIX_EPOCH + Duration::from_secs(i));
+    }
+    assert_eq!(queue.received, 10);
+    assert_eq!(queue.dropped, 6);
+    let payloads: Vec<String> = queue
+        .take()
+        .iter()
+        .map(|(s, _)| s.payload().try_to_string().unwrap().to_string())
+        .collect();
+    assert_eq!(payloads, vec!["0", "3", "6", "9"]);
+
+    // 计数在 take 之后继续, 不会重新从第一个开始
+    for i in 10..13 {
+        let sample = SampleBuilder::put(OwnedKeyExpr::new("demo/a").unwrap(), i.to_string()).into();
+        queue.push(sample, UNIX_EPOCH + Duration::from_secs(i));
+    }
+    assert_eq!(queue.take().len(), 1);
+    assert_eq!(queue.dropped, 8);
+}
+
+#[test]
+fn sub_queue_keep_latest() {
+    use std::time::UNIX_EPOCH;
+    use zenoh::sample::SampleBuilder;
+
+    let mut queue = SubQueue::new(2, SubDropPolicy::KeepLatest);
+    let keys = ["demo/a", "demo/b", "demo/a", "demo/c", "demo/d"];
+    for (i, key) in keys.iter().enumerate() {
+        let sample = SampleBuilder::put(OwnedKeyExpr::new(*key).unwrap(), i.to_string()).into();
+        queue.push(sample, UNIX_EPOCH + Duration::from_secs(i as u64));
+    }
+    assert_eq!(queue.received, 5);
+    // demo/a 被覆盖一次, demo/b 和 demo/a 因为新的 key 被替换
+    assert_eq!(queue.dropped, 3);
+    let keys: Vec<String> = queue
+        .take()
+        .iter()
+        .map(|(s, _)| s.key_expr().to_string())
+        .collect();
+    assert_eq!(keys, vec!["demo/c", "demo/d"]);
 }
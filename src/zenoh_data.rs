@@ -1,11 +1,13 @@
 use num_enum::{FromPrimitive, IntoPrimitive};
 use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
 use strum::{AsRefStr, EnumCount, EnumIter};
+use uhlc::NTP64;
 use zenoh::{
     bytes::{Encoding, ZBytes},
     qos::{CongestionControl, Priority, Reliability},
-    query::{ConsolidationMode, QueryConsolidation, QueryTarget},
-    sample::Locality,
+    query::{ConsolidationMode, QueryConsolidation, QueryTarget, ReplyKeyExpr},
+    sample::{Locality, SampleKind},
 };
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, FromPrimitive, IntoPrimitive, EnumIter, EnumCount)]
@@ -225,10 +227,11 @@ impl Into<Priority> for ZPriority {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Copy, AsRefStr, EnumIter, Eq, PartialEq, Default)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum ZReliability {
+    #[default]
     Reliable,
     BestEffort,
 }
@@ -312,6 +315,33 @@ impl Into<QueryConsolidation> for ZConsolidation {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, EnumIter, Eq, PartialEq, AsRefStr, Default)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ZReplyKeyExpr {
+    Any,
+    #[default]
+    MatchingQuery,
+}
+
+impl From<ReplyKeyExpr> for ZReplyKeyExpr {
+    fn from(value: ReplyKeyExpr) -> Self {
+        match value {
+            ReplyKeyExpr::Any => ZReplyKeyExpr::Any,
+            ReplyKeyExpr::MatchingQuery => ZReplyKeyExpr::MatchingQuery,
+        }
+    }
+}
+
+impl From<ZReplyKeyExpr> for ReplyKeyExpr {
+    fn from(value: ZReplyKeyExpr) -> Self {
+        match value {
+            ZReplyKeyExpr::Any => ReplyKeyExpr::Any,
+            ZReplyKeyExpr::MatchingQuery => ReplyKeyExpr::MatchingQuery,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, EnumIter, Eq, PartialEq, AsRefStr, Default)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -342,6 +372,33 @@ impl Into<Locality> for ZLocality {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, EnumIter, Eq, PartialEq, AsRefStr, Default)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ZSampleKind {
+    #[default]
+    Put,
+    Delete,
+}
+
+impl From<SampleKind> for ZSampleKind {
+    fn from(value: SampleKind) -> Self {
+        match value {
+            SampleKind::Put => ZSampleKind::Put,
+            SampleKind::Delete => ZSampleKind::Delete,
+        }
+    }
+}
+
+impl From<ZSampleKind> for SampleKind {
+    fn from(value: ZSampleKind) -> Self {
+        match value {
+            ZSampleKind::Put => SampleKind::Put,
+            ZSampleKind::Delete => SampleKind::Delete,
+        }
+    }
+}
+
 #[derive(Default, Copy, Clone, AsRefStr)]
 #[strum(serialize_all = "snake_case")]
 pub enum BytesType {
@@ -356,3 +413,8 @@ pub(crate) fn bytes_type(z_bytes: &ZBytes) -> BytesType {
         Some(_) => BytesType::Shm,
     }
 }
+
+pub(crate) fn system_time_to_string(t: SystemTime) -> String {
+    let d = t.duration_since(UNIX_EPOCH).unwrap_or_default();
+    NTP64::from(d).to_string_rfc3339_lossy()
+}